@@ -1,11 +1,16 @@
 use std::env;
+use std::error::Error;
 
-fn main() {
+use awair_local_api::Awair;
+
+fn main() -> Result<(), Box<dyn Error>> {
     // Use: demo <base url>
     // Example: demo 'http://192.168.1.10'
-    let api_base = env::args().nth(1).unwrap();
-    let client = awair_local_api::Awair::new(&api_base).unwrap();
+    let api_base = env::args().nth(1).ok_or("usage: demo <base url>")?;
+    let client: Awair = api_base.as_str().try_into()?;
+
+    println!("{:#?}", client.config()?);
+    println!("{:#?}", client.poll()?);
 
-    println!("{:#?}", client.config().unwrap());
-    println!("{:#?}", client.poll().unwrap());
+    Ok(())
 }
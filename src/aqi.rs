@@ -0,0 +1,112 @@
+//! US EPA Air Quality Index (AQI) computation.
+
+use crate::AirData;
+
+/// A single row of the EPA's PM2.5 breakpoint table: a concentration range
+/// (in micrograms per cubic meter) and its corresponding AQI range.
+struct Breakpoint {
+    concentration: (f32, f32),
+    index: (f32, f32),
+}
+
+/// The EPA's PM2.5 (24-hour) breakpoints, as revised in 2024.
+const PM25_BREAKPOINTS: &[Breakpoint] = &[
+    Breakpoint {
+        concentration: (0.0, 9.0),
+        index: (0.0, 50.0),
+    },
+    Breakpoint {
+        concentration: (9.1, 35.4),
+        index: (51.0, 100.0),
+    },
+    Breakpoint {
+        concentration: (35.5, 55.4),
+        index: (101.0, 150.0),
+    },
+    Breakpoint {
+        concentration: (55.5, 125.4),
+        index: (151.0, 200.0),
+    },
+    Breakpoint {
+        concentration: (125.5, 225.4),
+        index: (201.0, 300.0),
+    },
+    Breakpoint {
+        concentration: (225.5, 325.4),
+        index: (301.0, 500.0),
+    },
+];
+
+/// The highest AQI value; concentrations beyond the breakpoint table clamp to it.
+const MAX_AQI: u32 = 500;
+
+impl AirData {
+    /// The US EPA Air Quality Index for this sample's PM2.5 reading.
+    ///
+    /// This uses the EPA's piecewise-linear interpolation over its PM2.5
+    /// breakpoints (as revised in 2024), rounded to the nearest integer.
+    /// Concentrations above the highest breakpoint are clamped to 500.
+    ///
+    /// Returns `None` if the device doesn't report PM2.5.
+    ///
+    /// NOTE: The EPA defines the AQI over a 24-hour average, while this is
+    /// computed from a single sample. Consider using it on an averaged sample,
+    /// such as one from [`Awair::fifteen_minute_average`](crate::Awair::fifteen_minute_average).
+    pub fn us_aqi(&self) -> Option<u32> {
+        // The Awair reports whole-number concentrations, so each breakpoint's
+        // upper bound is sufficient to classify the reading without falling
+        // into the gaps between the table's ranges.
+        let concentration = self.pm25? as f32;
+
+        let aqi = PM25_BREAKPOINTS
+            .iter()
+            .find(|bp| concentration <= bp.concentration.1)
+            .map_or(MAX_AQI, |bp| {
+                let (c_lo, c_hi) = bp.concentration;
+                let (i_lo, i_hi) = bp.index;
+
+                ((i_hi - i_lo) / (c_hi - c_lo) * (concentration - c_lo) + i_lo).round() as u32
+            });
+
+        Some(aqi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    fn aqi(pm25: Option<u32>) -> Option<u32> {
+        let mut data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        data.pm25 = pm25;
+        data.us_aqi()
+    }
+
+    #[test]
+    fn test_us_aqi_breakpoints() {
+        for (pm25, expected) in [
+            (0, 0),
+            (9, 50),
+            (10, 53),
+            (35, 99),
+            (36, 102),
+            (55, 149),
+            (56, 151),
+            (125, 200),
+            (126, 201),
+            (225, 300),
+            (226, 302),
+            (325, 499),
+            (326, 500),
+            (1000, 500),
+        ] {
+            assert_eq!(aqi(Some(pm25)), Some(expected), "PM2.5 {pm25}");
+        }
+    }
+
+    #[test]
+    fn test_us_aqi_missing() {
+        assert_eq!(aqi(None), None);
+    }
+}
@@ -0,0 +1,102 @@
+//! A circuit breaker, for avoiding requests to a device that's down.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// The mutable state of a [`CircuitBreaker`].
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// A circuit breaker that opens after a number of consecutive transient
+/// failures, rejecting requests until a cooldown has elapsed.
+///
+/// Once the cooldown has elapsed, requests are allowed through again: a
+/// success closes the breaker, while another failure reopens it.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            state: Default::default(),
+        }
+    }
+
+    /// Returns an error if the breaker is open.
+    pub(crate) fn check(&self) -> Result<(), Error> {
+        match self.lock().open_until {
+            Some(open_until) if Instant::now() < open_until => Err(Error::CircuitOpen),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record the outcome of a request.
+    ///
+    /// Only transient errors count as failures, since any other outcome means
+    /// that the device is responding.
+    pub(crate) fn record<T>(&self, result: &Result<T, Error>) {
+        let mut state = self.lock();
+
+        match result {
+            Err(e) if e.is_transient() => {
+                state.failures = state.failures.saturating_add(1);
+                if state.failures >= self.threshold {
+                    state.open_until = Some(Instant::now() + self.cooldown);
+                }
+            }
+            _ => *state = State::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_breaker() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(100));
+        let timeout = Err::<(), _>(Error::Timeout(None));
+
+        breaker.record(&timeout);
+        assert!(breaker.check().is_ok());
+
+        // Non-transient failures don't count, and reset the count.
+        breaker.record(&Err::<(), _>(Error::NoBases));
+        breaker.record(&timeout);
+        assert!(breaker.check().is_ok());
+
+        breaker.record(&timeout);
+        assert!(matches!(breaker.check(), Err(Error::CircuitOpen)));
+
+        // After the cooldown, a trial request is allowed; another failure reopens.
+        thread::sleep(Duration::from_millis(150));
+        assert!(breaker.check().is_ok());
+        breaker.record(&timeout);
+        assert!(matches!(breaker.check(), Err(Error::CircuitOpen)));
+
+        // A success closes the breaker.
+        thread::sleep(Duration::from_millis(150));
+        breaker.record(&Ok(()));
+        assert!(breaker.check().is_ok());
+        breaker.record(&timeout);
+        assert!(breaker.check().is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+//! Thermal comfort and psychrometric computations from temperature and
+//! humidity readings.
+
+use std::fmt;
+
+use crate::{celsius_to_fahrenheit, AirData};
+
+/// The temperature range (in degrees Celsius) considered [`Comfort::Comfortable`].
+const COMFORTABLE_TEMPERATURE: (f32, f32) = (18.0, 26.0);
+
+/// The relative humidity range (as a percent) considered [`Comfort::Comfortable`].
+pub(crate) const COMFORTABLE_HUMIDITY: (f32, f32) = (30.0, 60.0);
+
+/// The Magnus formula's coefficients over water, per Sonntag (1990): a base
+/// saturation vapor pressure (in hectopascals), and two dimensionless and
+/// temperature (in degrees Celsius) coefficients.
+const MAGNUS: (f32, f32, f32) = (6.112, 17.62, 243.12);
+
+/// The specific gas constant for water vapor, in J/(kg·K).
+const WATER_VAPOR_GAS_CONSTANT: f32 = 461.5;
+
+/// Convert a temperature in degrees Fahrenheit to degrees Celsius.
+fn fahrenheit_to_celsius(fahrenheit: f32) -> f32 {
+    (fahrenheit - 32.0) * 5.0 / 9.0
+}
+
+/// A qualitative classification of thermal comfort.
+///
+/// Temperature takes precedence over humidity: a sample is only classified
+/// by its humidity if its temperature is comfortable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comfort {
+    /// Below 18°C.
+    TooCold,
+    /// Above 26°C.
+    TooHot,
+    /// A comfortable temperature, but below 30% relative humidity.
+    TooDry,
+    /// A comfortable temperature, but above 60% relative humidity.
+    TooHumid,
+    /// From 18°C to 26°C, and from 30% to 60% relative humidity.
+    Comfortable,
+}
+
+impl fmt::Display for Comfort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooCold => "too cold",
+            Self::TooHot => "too hot",
+            Self::TooDry => "too dry",
+            Self::TooHumid => "too humid",
+            Self::Comfortable => "comfortable",
+        })
+    }
+}
+
+impl AirData {
+    /// The heat index ("feels like" temperature), in degrees Celsius.
+    ///
+    /// This uses the US National Weather Service's formulation: Steadman's
+    /// simple approximation for mild conditions, and the Rothfusz regression
+    /// (with the NWS's low and high humidity adjustments) otherwise.
+    pub fn heat_index_celsius(&self) -> f32 {
+        let t = celsius_to_fahrenheit(self.temperature);
+        let rh = self.humidity;
+
+        let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+        if (simple + t) / 2.0 < 80.0 {
+            return fahrenheit_to_celsius(simple);
+        }
+
+        let mut hi = -42.379 + 2.049_015_2 * t + 10.143_331 * rh
+            - 0.224_755_4 * t * rh
+            - 0.006_837_83 * t * t
+            - 0.054_817_17 * rh * rh
+            + 0.001_228_74 * t * t * rh
+            + 0.000_852_82 * t * rh * rh
+            - 0.000_001_99 * t * t * rh * rh;
+
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            hi -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            hi += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+
+        fahrenheit_to_celsius(hi)
+    }
+
+    /// Classify this sample's thermal comfort, from its temperature and
+    /// relative humidity.
+    pub fn comfort(&self) -> Comfort {
+        let (min_temperature, max_temperature) = COMFORTABLE_TEMPERATURE;
+        let (min_humidity, max_humidity) = COMFORTABLE_HUMIDITY;
+
+        if self.temperature < min_temperature {
+            Comfort::TooCold
+        } else if self.temperature > max_temperature {
+            Comfort::TooHot
+        } else if self.humidity < min_humidity {
+            Comfort::TooDry
+        } else if self.humidity > max_humidity {
+            Comfort::TooHumid
+        } else {
+            Comfort::Comfortable
+        }
+    }
+
+    /// The absolute humidity, in grams per cubic meter, recomputed from this
+    /// sample's temperature and relative humidity with the Magnus formula.
+    pub fn recomputed_absolute_humidity(&self) -> f32 {
+        let (base, a, b) = MAGNUS;
+        let t = self.temperature;
+
+        // Vapor pressure, in pascals.
+        let vapor_pressure = base * 100.0 * (a * t / (b + t)).exp() * self.humidity / 100.0;
+
+        vapor_pressure / (WATER_VAPOR_GAS_CONSTANT * (t + 273.15)) * 1000.0
+    }
+
+    /// The difference between the reported absolute humidity and
+    /// [`AirData::recomputed_absolute_humidity`], in grams per cubic meter.
+    ///
+    /// A consistently large discrepancy may indicate a miscalibrated sensor.
+    pub fn absolute_humidity_discrepancy(&self) -> f32 {
+        self.absolute_humidity - self.recomputed_absolute_humidity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    /// Returns the mock's sample, with the given temperature (in degrees
+    /// Celsius) and relative humidity.
+    fn sample(temperature: f32, humidity: f32) -> AirData {
+        let mut sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        sample.temperature = temperature;
+        sample.humidity = humidity;
+        sample
+    }
+
+    #[test]
+    fn test_heat_index() {
+        // Reference values from the NWS heat index chart, in degrees Fahrenheit.
+        for (temperature, humidity, expected) in [
+            (80.0, 40.0, 80.0),
+            (90.0, 50.0, 95.0),
+            (100.0, 40.0, 109.0),
+            (86.0, 90.0, 105.0),
+        ] {
+            let heat_index = celsius_to_fahrenheit(
+                sample(fahrenheit_to_celsius(temperature), humidity).heat_index_celsius(),
+            );
+            assert!(
+                (heat_index - expected).abs() < 1.0,
+                "{temperature}°F at {humidity}%: expected {expected}, got {heat_index}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_comfort() {
+        assert_eq!(sample(17.9, 45.0).comfort(), Comfort::TooCold);
+        assert_eq!(sample(26.1, 45.0).comfort(), Comfort::TooHot);
+        assert_eq!(sample(21.0, 29.0).comfort(), Comfort::TooDry);
+        assert_eq!(sample(21.0, 61.0).comfort(), Comfort::TooHumid);
+        assert_eq!(sample(18.0, 30.0).comfort(), Comfort::Comfortable);
+        assert_eq!(sample(26.0, 60.0).comfort(), Comfort::Comfortable);
+
+        // Temperature takes precedence over humidity.
+        assert_eq!(sample(30.0, 90.0).comfort(), Comfort::TooHot);
+    }
+
+    #[test]
+    fn test_absolute_humidity() {
+        // Reference values from psychrometric tables, in grams per cubic meter.
+        for (temperature, humidity, expected) in
+            [(0.0, 100.0, 4.85), (20.0, 50.0, 8.65), (30.0, 80.0, 24.3)]
+        {
+            let recomputed = sample(temperature, humidity).recomputed_absolute_humidity();
+            assert!(
+                (recomputed - expected).abs() < 0.1,
+                "{temperature}°C at {humidity}%: expected {expected}, got {recomputed}"
+            );
+        }
+
+        // The mock's sample is consistent with its own readings.
+        let mut data = sample(21.4, 42.1);
+        assert!(data.absolute_humidity_discrepancy().abs() < 0.1);
+
+        data.absolute_humidity += 3.0;
+        assert!((data.absolute_humidity_discrepancy() - 3.0).abs() < 0.1);
+    }
+}
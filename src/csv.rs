@@ -0,0 +1,157 @@
+//! CSV serialization for [`AirData`].
+
+use std::io::{self, Write};
+
+use crate::AirData;
+
+/// The CSV columns for an [`AirData`] record, in order.
+///
+/// These match the field names used by the Local API.
+const COLUMNS: &[&str] = &[
+    "timestamp",
+    "score",
+    "dew_point",
+    "temp",
+    "humid",
+    "abs_humid",
+    "co2",
+    "co2_est",
+    "co2_est_baseline",
+    "voc",
+    "voc_baseline",
+    "voc_h2_raw",
+    "voc_ethanol_raw",
+    "pm25",
+    "pm10_est",
+    "lux",
+    "spl_a",
+];
+
+/// Format an optional column, leaving it empty if absent.
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+impl AirData {
+    /// Returns the CSV header line corresponding to [`AirData::to_csv_row`].
+    ///
+    /// The header uses the same field names as the Local API, and has no
+    /// trailing newline.
+    pub fn csv_header() -> String {
+        COLUMNS.join(",")
+    }
+
+    /// Returns this sample as a CSV record, in the same column order as
+    /// [`AirData::csv_header`].
+    ///
+    /// The timestamp is formatted as RFC 3339, and every other column is a plain
+    /// number. Model-dependent fields that the device doesn't report are left
+    /// empty. None of these values can contain a delimiter or quote, so the
+    /// record never requires quoting. The record has no trailing newline.
+    pub fn to_csv_row(&self) -> String {
+        [
+            self.timestamp.to_rfc3339(),
+            self.score.to_string(),
+            self.dew_point.to_string(),
+            self.temperature.to_string(),
+            self.humidity.to_string(),
+            self.absolute_humidity.to_string(),
+            self.co2.to_string(),
+            optional(self.estimated_co2),
+            optional(self.estimated_co2_baseline),
+            self.voc.to_string(),
+            self.voc_baseline.to_string(),
+            self.voc_h2_raw.to_string(),
+            self.voc_ethanol_raw.to_string(),
+            optional(self.pm25),
+            optional(self.estimated_pm10),
+            optional(self.lux),
+            optional(self.spl_a),
+        ]
+        .join(",")
+    }
+}
+
+/// Write some samples as CSV: a header line, followed by one record per
+/// sample (as in [`AirData::to_csv_row`]).
+///
+/// Every line, including the last, ends with a newline. If there are no
+/// samples, only the header is written.
+pub fn write_csv<W: Write>(samples: &[AirData], w: &mut W) -> io::Result<()> {
+    writeln!(w, "{}", AirData::csv_header())?;
+    for sample in samples {
+        writeln!(w, "{}", sample.to_csv_row())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    /// Parse a CSV record back into a sample, using the given header.
+    fn parse(header: &str, row: &str) -> AirData {
+        let fields = header
+            .split(',')
+            .zip(row.split(','))
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(column, value)| {
+                let value = match column {
+                    "timestamp" => value.into(),
+                    _ => match value.parse::<u64>() {
+                        Ok(value) => value.into(),
+                        Err(_) => value.parse::<f64>().unwrap().into(),
+                    },
+                };
+                (column.to_string(), value)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        serde_json::from_value(fields.into()).unwrap()
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let mut sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        sample.lux = Some(120.5);
+
+        let header = AirData::csv_header();
+        let row = sample.to_csv_row();
+        assert_eq!(header.split(',').count(), row.split(',').count());
+        assert_eq!(parse(&header, &row), sample);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let samples = (0..3)
+            .map(|i| {
+                let mut sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+                sample.timestamp += chrono::Duration::minutes(i);
+                sample.co2 += i as u32 * 100;
+                sample
+            })
+            .collect::<Vec<_>>();
+
+        let mut buf = Vec::new();
+        write_csv(&samples, &mut buf).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        assert!(csv.ends_with('\n'));
+
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap();
+        assert_eq!(header, AirData::csv_header());
+        assert_eq!(
+            lines.map(|row| parse(header, row)).collect::<Vec<_>>(),
+            samples
+        );
+
+        let mut buf = Vec::new();
+        write_csv(&[], &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            format!("{}\n", AirData::csv_header())
+        );
+    }
+}
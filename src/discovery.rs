@@ -0,0 +1,192 @@
+//! Discovery of Awair devices on the local network, via mDNS.
+
+use std::collections::HashSet;
+use std::net::Ipv4Addr;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use futures_util::{stream, Stream};
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+
+#[cfg(feature = "async")]
+use crate::AwairAsync;
+use crate::{Awair, Error};
+
+/// The mDNS service type browsed by [`discover`].
+///
+/// Awair devices with the Local API enabled advertise themselves as plain
+/// HTTP services, with instance names beginning with `AWAIR` (for example,
+/// `AWAIR-ELEM-1419E1._http._tcp.local.`).
+pub const SERVICE_TYPE: &str = "_http._tcp.local.";
+
+/// The instance name prefix that distinguishes Awair devices from other
+/// HTTP services on the network.
+const INSTANCE_PREFIX: &str = "awair";
+
+/// Discover Awair devices on the local network.
+///
+/// This browses for [`SERVICE_TYPE`] services for up to `timeout`, and
+/// returns one [`Awair`] client for each Awair device that responds, using
+/// the IPv4 address and port (normally 80) that the device advertises.
+/// Devices that advertise themselves more than once are only returned once.
+///
+/// Finding no devices is not an error: an empty `Vec` is returned instead.
+pub fn discover(timeout: Duration) -> Result<Vec<Awair>, Error> {
+    let mdns = ServiceDaemon::new()?;
+    let events = mdns.browse(SERVICE_TYPE)?;
+
+    let deadline = Instant::now() + timeout;
+    let mut hosts = HashSet::new();
+    let mut devices = vec![];
+
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        let Ok(event) = events.recv_timeout(remaining) else {
+            break;
+        };
+
+        if let Some((addr, port)) = resolve(event, &mut hosts) {
+            devices.push(Awair::from_host_port(&addr.to_string(), port)?);
+        }
+    }
+
+    // Failing to shut down cleanly doesn't invalidate anything we've discovered.
+    let _ = mdns.shutdown();
+
+    Ok(devices)
+}
+
+/// Discover Awair devices on the local network, yielding each one as soon as
+/// it's found.
+///
+/// This is the streaming counterpart to [`discover`], yielding [`AwairAsync`]
+/// clients: the stream ends once `timeout` has elapsed, even if devices are
+/// still responding. It must be polled within a Tokio runtime.
+#[cfg(feature = "async")]
+pub fn discover_stream(timeout: Duration) -> Result<impl Stream<Item = AwairAsync>, Error> {
+    let mdns = ServiceDaemon::new()?;
+    let events = mdns.browse(SERVICE_TYPE)?;
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    let browser = Browser {
+        mdns,
+        events,
+        hosts: HashSet::new(),
+    };
+
+    Ok(stream::unfold(browser, move |mut browser| async move {
+        loop {
+            let event = tokio::time::timeout_at(deadline, browser.events.recv_async())
+                .await
+                .ok()?
+                .ok()?;
+
+            // NOTE: Unlike `discover`, we skip devices whose advertised
+            // addresses can't be turned into a client.
+            let Some((addr, port)) = resolve(event, &mut browser.hosts) else {
+                continue;
+            };
+            if let Ok(device) = AwairAsync::from_host_port(&addr.to_string(), port) {
+                return Some((device, browser));
+            }
+        }
+    }))
+}
+
+/// An in-progress mDNS browse, which is shut down when dropped.
+#[cfg(feature = "async")]
+struct Browser {
+    mdns: ServiceDaemon,
+    events: mdns_sd::Receiver<ServiceEvent>,
+    hosts: HashSet<String>,
+}
+
+#[cfg(feature = "async")]
+impl Drop for Browser {
+    fn drop(&mut self) {
+        let _ = self.mdns.shutdown();
+    }
+}
+
+/// Turn a browse event into the address and port of an Awair device, if it
+/// resolves one whose host isn't in `hosts` yet.
+fn resolve(event: ServiceEvent, hosts: &mut HashSet<String>) -> Option<(Ipv4Addr, u16)> {
+    let ServiceEvent::ServiceResolved(service) = event else {
+        return None;
+    };
+
+    if !service
+        .get_fullname()
+        .to_ascii_lowercase()
+        .starts_with(INSTANCE_PREFIX)
+    {
+        return None;
+    }
+
+    let addr = service.get_addresses_v4().into_iter().min()?;
+
+    if !hosts.insert(service.get_hostname().to_string()) {
+        return None;
+    }
+
+    Some((addr, service.get_port()))
+}
+
+#[cfg(test)]
+mod tests {
+    use mdns_sd::ServiceInfo;
+
+    use super::*;
+
+    /// Advertise fake Awair devices (and one other HTTP service) over mDNS,
+    /// until the returned daemon is shut down.
+    fn advertise() -> ServiceDaemon {
+        let responder = ServiceDaemon::new().unwrap();
+        for (name, host, port) in [
+            ("AWAIR-ELEM-000001", "awair-elem-000001.local.", 8081),
+            ("AWAIR-ELEM-000002", "awair-elem-000002.local.", 8082),
+            ("printer", "printer.local.", 8083),
+        ] {
+            let service = ServiceInfo::new(SERVICE_TYPE, name, host, "", port, None)
+                .unwrap()
+                .enable_addr_auto();
+            responder.register(service).unwrap();
+        }
+        responder
+    }
+
+    /// The ports of the discovered devices, in ascending order.
+    fn ports<'a>(bases: impl Iterator<Item = &'a url::Url>) -> Vec<u16> {
+        let mut ports = bases.filter_map(url::Url::port).collect::<Vec<_>>();
+        ports.sort_unstable();
+        ports
+    }
+
+    #[test]
+    fn test_discover() {
+        let responder = advertise();
+        let devices = discover(Duration::from_secs(3)).unwrap();
+        let _ = responder.shutdown();
+
+        assert_eq!(ports(devices.iter().map(Awair::api_base)), [8081, 8082]);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_discover_stream() {
+        use futures_util::StreamExt;
+
+        let responder = advertise();
+        let start = Instant::now();
+        let devices = discover_stream(Duration::from_secs(3))
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        let _ = responder.shutdown();
+
+        assert_eq!(
+            ports(devices.iter().map(AwairAsync::api_base)),
+            [8081, 8082]
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
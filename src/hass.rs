@@ -0,0 +1,210 @@
+//! Home Assistant integration, via MQTT discovery.
+//!
+//! This module doesn't depend on any particular MQTT client: instead, the
+//! client is wrapped in an [`MqttPublish`] implementation (or a closure).
+//!
+//! Each Awair is identified by a node ID derived from its
+//! [`DeviceConfig::device_id`], with any characters other than ASCII
+//! alphanumerics, `-`, and `_` replaced by `_`. The topics used are:
+//!
+//! * `homeassistant/sensor/<node_id>/<sensor>/config`: the (retained)
+//!   discovery config for each sensor, where `<sensor>` is the sensor's
+//!   Local API field name (e.g. `temp`). Each sensor's unique ID is
+//!   `<node_id>_<sensor>`.
+//! * `awair/<node_id>/state`: the state of every sensor, as a single JSON
+//!   object in the same format as the Local API's.
+
+use serde_json::json;
+
+use crate::{AirData, DeviceConfig};
+
+/// The default Home Assistant MQTT discovery prefix.
+pub const DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// The prefix for state topics.
+const STATE_PREFIX: &str = "awair";
+
+/// The sensors exposed to Home Assistant: each one's Local API field name,
+/// display name, and Home Assistant device class (if any).
+const SENSORS: &[(&str, &str, Option<&str>)] = &[
+    ("score", "Awair Score", None),
+    ("dew_point", "Dew point", Some("temperature")),
+    ("temp", "Temperature", Some("temperature")),
+    ("humid", "Humidity", Some("humidity")),
+    ("abs_humid", "Absolute humidity", Some("absolute_humidity")),
+    ("co2", "CO2", Some("carbon_dioxide")),
+    ("voc", "VOC", Some("volatile_organic_compounds_parts")),
+    ("pm25", "PM2.5", Some("pm25")),
+    ("pm10_est", "PM10", Some("pm10")),
+    ("lux", "Illuminance", Some("illuminance")),
+    ("spl_a", "Sound pressure", Some("sound_pressure")),
+];
+
+/// A handle to an MQTT client, capable of publishing messages.
+///
+/// This is implemented for closures of the form
+/// `FnMut(topic, payload, retain) -> Result<(), E>`.
+pub trait MqttPublish {
+    /// The error returned when publishing fails.
+    type Error;
+
+    /// Publish `payload` to `topic`, asking the broker to retain it if `retain` is set.
+    fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Result<(), Self::Error>;
+}
+
+impl<F, E> MqttPublish for F
+where
+    F: FnMut(&str, &[u8], bool) -> Result<(), E>,
+{
+    type Error = E;
+
+    fn publish(&mut self, topic: &str, payload: &[u8], retain: bool) -> Result<(), E> {
+        self(topic, payload, retain)
+    }
+}
+
+/// Returns the node ID used for the given device in topics and unique IDs.
+pub fn node_id(config: &DeviceConfig) -> String {
+    config
+        .device_id
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Returns the topic that the given device's state is published to.
+pub fn state_topic(config: &DeviceConfig) -> String {
+    format!("{STATE_PREFIX}/{}/state", node_id(config))
+}
+
+/// Publish a discovery config for each of the device's sensors.
+///
+/// Only the sensors reported by `sample` are published, so that sensors
+/// the device's model doesn't have don't appear in Home Assistant.
+pub fn publish_discovery<P: MqttPublish>(
+    publisher: &mut P,
+    config: &DeviceConfig,
+    sample: &AirData,
+) -> Result<(), P::Error> {
+    let node_id = node_id(config);
+    let state_topic = state_topic(config);
+    let device = json!({
+        "identifiers": [config.device_id],
+        "name": format!("Awair {}", config.device_id),
+        "manufacturer": "Awair",
+        "sw_version": config.firmware_version,
+    });
+
+    for reading in sample.readings() {
+        let sensor = SENSORS.iter().find(|(key, ..)| *key == reading.name);
+        let Some(&(key, name, device_class)) = sensor else {
+            continue;
+        };
+
+        let mut payload = json!({
+            "name": name,
+            "unique_id": format!("{node_id}_{key}"),
+            "state_topic": state_topic,
+            "value_template": format!("{{{{ value_json.{key} }}}}"),
+            "state_class": "measurement",
+            "device": device,
+        });
+        if !reading.unit.is_empty() {
+            payload["unit_of_measurement"] = reading.unit.into();
+        }
+        if let Some(device_class) = device_class {
+            payload["device_class"] = device_class.into();
+        }
+
+        publisher.publish(
+            &format!("{DISCOVERY_PREFIX}/sensor/{node_id}/{key}/config"),
+            payload.to_string().as_bytes(),
+            true,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Publish the given sample as the device's state.
+pub fn publish_state<P: MqttPublish>(
+    publisher: &mut P,
+    config: &DeviceConfig,
+    sample: &AirData,
+) -> Result<(), P::Error> {
+    // NOTE: Serializing an `AirData` can't fail, since all of its map keys are strings.
+    let state = serde_json::to_vec(sample).unwrap_or_default();
+
+    publisher.publish(&state_topic(config), &state, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::testing::{SAMPLE_AIR_DATA, SAMPLE_CONFIG};
+
+    /// Publish with the given function, returning each published message.
+    fn capture(
+        publish: impl FnOnce(&mut dyn FnMut(&str, &[u8], bool) -> Result<(), Infallible>),
+    ) -> Vec<(String, Value, bool)> {
+        let mut messages = Vec::new();
+        publish(&mut |topic: &str, payload: &[u8], retain: bool| {
+            messages.push((
+                topic.to_string(),
+                serde_json::from_slice(payload).unwrap(),
+                retain,
+            ));
+            Ok(())
+        });
+        messages
+    }
+
+    #[test]
+    fn test_publish_discovery() {
+        let config: DeviceConfig = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+        let sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        let messages =
+            capture(|mut publisher| publish_discovery(&mut publisher, &config, &sample).unwrap());
+
+        // The sample doesn't report light or sound, so those aren't announced.
+        assert_eq!(messages.len(), 9);
+        assert!(messages.iter().all(|(_, _, retain)| *retain));
+
+        let (topic, payload, _) = &messages[2];
+        assert_eq!(topic, "homeassistant/sensor/awair-element_1234/temp/config");
+        assert_eq!(payload["unique_id"], "awair-element_1234_temp");
+        assert_eq!(payload["state_topic"], "awair/awair-element_1234/state");
+        assert_eq!(payload["value_template"], "{{ value_json.temp }}");
+        assert_eq!(payload["unit_of_measurement"], "°C");
+        assert_eq!(payload["device_class"], "temperature");
+        assert_eq!(payload["device"]["identifiers"][0], "awair-element_1234");
+
+        // The score is unitless, and has no device class.
+        let (_, score, _) = &messages[0];
+        assert!(score.get("unit_of_measurement").is_none());
+        assert!(score.get("device_class").is_none());
+    }
+
+    #[test]
+    fn test_publish_state() {
+        let mut config: DeviceConfig = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+        config.device_id = "awair element/1".into();
+        let sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        let messages =
+            capture(|mut publisher| publish_state(&mut publisher, &config, &sample).unwrap());
+
+        let [(topic, payload, retain)] = messages.as_slice() else {
+            panic!("expected one message, got {messages:?}");
+        };
+        assert_eq!(topic, "awair/awair_element_1/state");
+        assert_eq!(payload["co2"], 612);
+        assert!(!retain);
+    }
+}
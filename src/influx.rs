@@ -0,0 +1,91 @@
+//! InfluxDB line protocol serialization for [`AirData`].
+
+use std::fmt::Write;
+
+use crate::AirData;
+
+/// Escape a measurement name for the line protocol.
+fn escape_measurement(measurement: &str) -> String {
+    measurement.replace(',', "\\,").replace(' ', "\\ ")
+}
+
+impl AirData {
+    /// Returns this sample as a single InfluxDB line protocol point, with the
+    /// given measurement name.
+    ///
+    /// The point's field keys are the field names used by the Local API
+    /// (`score`, `dew_point`, `temp`, `humid`, `abs_humid`, `co2`, `co2_est`,
+    /// `co2_est_baseline`, `voc`, `voc_baseline`, `voc_h2_raw`, `voc_ethanol_raw`,
+    /// `pm25`, `pm10_est`, `lux`, and `spl_a`). Integer readings are written as integer fields,
+    /// and model-dependent fields are omitted when the device doesn't report them.
+    ///
+    /// The point's timestamp is the sample's timestamp, in nanoseconds since
+    /// the Unix epoch. The line has no trailing newline.
+    pub fn to_line_protocol(&self, measurement: &str) -> String {
+        let floats = [
+            ("dew_point", Some(self.dew_point)),
+            ("temp", Some(self.temperature)),
+            ("humid", Some(self.humidity)),
+            ("abs_humid", Some(self.absolute_humidity)),
+            ("lux", self.lux),
+            ("spl_a", self.spl_a),
+        ];
+
+        let integers = [
+            ("score", Some(self.score.into())),
+            ("co2", Some(self.co2)),
+            ("co2_est", self.estimated_co2),
+            ("co2_est_baseline", self.estimated_co2_baseline),
+            ("voc", Some(self.voc)),
+            ("voc_baseline", Some(self.voc_baseline)),
+            ("voc_h2_raw", Some(self.voc_h2_raw)),
+            ("voc_ethanol_raw", Some(self.voc_ethanol_raw)),
+            ("pm25", self.pm25),
+            ("pm10_est", self.estimated_pm10),
+        ];
+
+        let fields = floats
+            .iter()
+            .filter_map(|(key, value)| value.map(|value| format!("{key}={value}")))
+            .chain(
+                integers
+                    .iter()
+                    .filter_map(|(key, value)| value.map(|value| format!("{key}={value}i"))),
+            )
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut line = format!("{} {fields}", escape_measurement(measurement));
+
+        // Timestamps outside of the range representable in nanoseconds
+        // (roughly 1677 to 2262) are omitted, leaving InfluxDB to assign one.
+        if let Some(nanos) = self.timestamp.timestamp_nanos_opt() {
+            let _ = write!(line, " {nanos}");
+        }
+
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    #[test]
+    fn test_to_line_protocol() {
+        let mut data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        assert_eq!(
+            data.to_line_protocol("air quality,room"),
+            "air\\ quality\\,room dew_point=8.5,temp=21.4,humid=42.1,abs_humid=7.9,\
+             score=90i,co2=612i,co2_est=450i,co2_est_baseline=35000i,voc=210i,\
+             voc_baseline=37000i,voc_h2_raw=26i,voc_ethanol_raw=38i,pm25=3i,pm10_est=4i \
+             1704067200000000000"
+        );
+
+        data.timestamp += chrono::Duration::nanoseconds(1);
+        assert!(data
+            .to_line_protocol("awair")
+            .ends_with(" 1704067200000000001"));
+    }
+}
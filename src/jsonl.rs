@@ -0,0 +1,55 @@
+//! JSON Lines logging for [`AirData`].
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::AirData;
+
+/// Append a sample to the JSON Lines file at `path`, creating it if needed.
+///
+/// The sample is written as a single JSON object followed by a newline, in
+/// one write to a file opened in append mode, so that concurrent appends from
+/// the same process don't interleave.
+pub fn append_jsonl(sample: &AirData, path: &Path) -> io::Result<()> {
+    let mut line = serde_json::to_vec(sample)?;
+    line.push(b'\n');
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    #[test]
+    fn test_append_jsonl() {
+        let path = std::env::temp_dir().join(format!("awair-jsonl-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let first: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        let mut second = first.clone();
+        second.timestamp += chrono::Duration::seconds(10);
+        second.co2 = 700;
+
+        append_jsonl(&first, &path).unwrap();
+        append_jsonl(&second, &path).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(contents.ends_with('\n'));
+        let samples = contents
+            .lines()
+            .map(|line| serde_json::from_str::<AirData>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(samples, [first, second]);
+    }
+}
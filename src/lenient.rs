@@ -0,0 +1,46 @@
+//! Lenient deserializers for numeric fields, which some firmware versions
+//! send as JSON strings (e.g. `"co2": "512"`).
+
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer};
+
+/// A number, either as itself or as a string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Lenient<T> {
+    Number(T),
+    String(String),
+}
+
+impl<T: FromStr> Lenient<T> {
+    fn into_number<E: de::Error>(self) -> Result<T, E> {
+        match self {
+            Self::Number(number) => Ok(number),
+            Self::String(string) => string
+                .trim()
+                .parse()
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(&string), &"a number")),
+        }
+    }
+}
+
+/// Deserialize a number, or a string containing one.
+pub(crate) fn number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+{
+    Lenient::deserialize(deserializer)?.into_number()
+}
+
+/// Deserialize an optional number, or a string containing one.
+pub(crate) fn optional_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+{
+    Option::<Lenient<T>>::deserialize(deserializer)?
+        .map(Lenient::into_number)
+        .transpose()
+}
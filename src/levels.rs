@@ -0,0 +1,425 @@
+//! Qualitative classifications of air quality readings.
+//!
+//! By default, the thresholds used here follow the ranges that Awair
+//! publishes for its own apps and devices; see [`Thresholds`] for others.
+
+use std::fmt;
+
+use crate::{AirData, Error};
+
+/// The lower bounds (in parts per million) of the [`Co2Level::Acceptable`],
+/// [`Co2Level::Poor`], and [`Co2Level::Dangerous`] levels, respectively.
+const CO2_THRESHOLDS: [u32; 3] = [600, 1000, 2500];
+
+/// The lower bounds (in parts per billion) of the [`VocLevel::Acceptable`],
+/// [`VocLevel::Poor`], and [`VocLevel::Dangerous`] levels, respectively.
+const VOC_THRESHOLDS: [u32; 3] = [333, 1000, 3333];
+
+/// The lower bounds (in micrograms per cubic meter) of the
+/// [`Pm25Level::Acceptable`], [`Pm25Level::Poor`], and [`Pm25Level::Dangerous`]
+/// levels, respectively.
+const PM25_THRESHOLDS: [u32; 3] = [15, 35, 55];
+
+/// The lower bounds of the [`ScoreRating::Fair`] and [`ScoreRating::Good`]
+/// ratings, respectively.
+const SCORE_THRESHOLDS: [u8; 2] = [60, 80];
+
+/// Configurable thresholds for classifying readings, for standards other
+/// than Awair's (e.g. ASHRAE's).
+///
+/// Each field holds the lower bounds of a classification's levels, in
+/// ascending order, excluding the lowest level: for example, [`Thresholds::co2`]
+/// holds the lower bounds of [`Co2Level::Acceptable`], [`Co2Level::Poor`],
+/// and [`Co2Level::Dangerous`]. The default thresholds are Awair's.
+///
+/// Each field's bounds must be strictly ascending, or classifications will
+/// skip levels; [`Thresholds::new`] checks this.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Thresholds {
+    /// The CO2 thresholds, in parts per million.
+    pub co2: [u32; 3],
+    /// The TVOC thresholds, in parts per billion.
+    pub voc: [u32; 3],
+    /// The PM2.5 thresholds, in micrograms per cubic meter.
+    pub pm25: [u32; 3],
+    /// The Awair Score thresholds, for [`ScoreRating::Fair`] and
+    /// [`ScoreRating::Good`].
+    pub score: [u8; 2],
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            co2: CO2_THRESHOLDS,
+            voc: VOC_THRESHOLDS,
+            pm25: PM25_THRESHOLDS,
+            score: SCORE_THRESHOLDS,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Create a new set of thresholds, checking that each field's bounds are
+    /// strictly ascending.
+    pub fn new(
+        co2: [u32; 3],
+        voc: [u32; 3],
+        pm25: [u32; 3],
+        score: [u8; 2],
+    ) -> Result<Self, Error> {
+        check_ascending("CO2", &co2)?;
+        check_ascending("TVOC", &voc)?;
+        check_ascending("PM2.5", &pm25)?;
+        check_ascending("score", &score)?;
+
+        Ok(Self {
+            co2,
+            voc,
+            pm25,
+            score,
+        })
+    }
+}
+
+/// Returns an [`Error::InvalidThresholds`] unless `thresholds` is strictly
+/// ascending.
+fn check_ascending<T: PartialOrd>(field: &'static str, thresholds: &[T]) -> Result<(), Error> {
+    if thresholds.windows(2).all(|pair| pair[0] < pair[1]) {
+        Ok(())
+    } else {
+        Err(Error::InvalidThresholds(field))
+    }
+}
+
+/// Returns the number of thresholds that `value` meets or exceeds.
+fn band<T: PartialOrd>(value: T, thresholds: &[T]) -> usize {
+    thresholds
+        .iter()
+        .take_while(|threshold| value >= **threshold)
+        .count()
+}
+
+/// A qualitative classification of a CO2 reading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Co2Level {
+    /// Below 600 ppm (under the [default thresholds](Thresholds::default)).
+    Good,
+    /// From 600 ppm, up to 1000 ppm (under the [default thresholds](Thresholds::default)).
+    Acceptable,
+    /// From 1000 ppm, up to 2500 ppm (under the [default thresholds](Thresholds::default)).
+    Poor,
+    /// 2500 ppm and above (under the [default thresholds](Thresholds::default)).
+    Dangerous,
+}
+
+impl Co2Level {
+    fn classify(ppm: u32, thresholds: &[u32; 3]) -> Self {
+        match band(ppm, thresholds) {
+            0 => Self::Good,
+            1 => Self::Acceptable,
+            2 => Self::Poor,
+            _ => Self::Dangerous,
+        }
+    }
+}
+
+impl fmt::Display for Co2Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Good => "good",
+            Self::Acceptable => "acceptable",
+            Self::Poor => "poor",
+            Self::Dangerous => "dangerous",
+        })
+    }
+}
+
+/// A qualitative classification of a TVOC reading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VocLevel {
+    /// Below 333 ppb (under the [default thresholds](Thresholds::default)).
+    Good,
+    /// From 333 ppb, up to 1000 ppb (under the [default thresholds](Thresholds::default)).
+    Acceptable,
+    /// From 1000 ppb, up to 3333 ppb (under the [default thresholds](Thresholds::default)).
+    Poor,
+    /// 3333 ppb and above (under the [default thresholds](Thresholds::default)).
+    Dangerous,
+}
+
+impl VocLevel {
+    fn classify(ppb: u32, thresholds: &[u32; 3]) -> Self {
+        match band(ppb, thresholds) {
+            0 => Self::Good,
+            1 => Self::Acceptable,
+            2 => Self::Poor,
+            _ => Self::Dangerous,
+        }
+    }
+}
+
+impl fmt::Display for VocLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Good => "good",
+            Self::Acceptable => "acceptable",
+            Self::Poor => "poor",
+            Self::Dangerous => "dangerous",
+        })
+    }
+}
+
+/// A qualitative classification of a PM2.5 reading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Pm25Level {
+    /// Below 15 μg/m³ (under the [default thresholds](Thresholds::default)).
+    Good,
+    /// From 15 μg/m³, up to 35 μg/m³ (under the [default thresholds](Thresholds::default)).
+    Acceptable,
+    /// From 35 μg/m³, up to 55 μg/m³ (under the [default thresholds](Thresholds::default)).
+    Poor,
+    /// 55 μg/m³ and above (under the [default thresholds](Thresholds::default)).
+    Dangerous,
+}
+
+impl Pm25Level {
+    fn classify(concentration: u32, thresholds: &[u32; 3]) -> Self {
+        match band(concentration, thresholds) {
+            0 => Self::Good,
+            1 => Self::Acceptable,
+            2 => Self::Poor,
+            _ => Self::Dangerous,
+        }
+    }
+}
+
+impl fmt::Display for Pm25Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Good => "good",
+            Self::Acceptable => "acceptable",
+            Self::Poor => "poor",
+            Self::Dangerous => "dangerous",
+        })
+    }
+}
+
+/// A qualitative classification of an Awair Score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScoreRating {
+    /// Below 60 (under the [default thresholds](Thresholds::default)).
+    Poor,
+    /// From 60, up to 80 (under the [default thresholds](Thresholds::default)).
+    Fair,
+    /// 80 and above (under the [default thresholds](Thresholds::default)).
+    Good,
+}
+
+impl ScoreRating {
+    fn classify(score: u8, thresholds: &[u8; 2]) -> Self {
+        match band(score, thresholds) {
+            0 => Self::Poor,
+            1 => Self::Fair,
+            _ => Self::Good,
+        }
+    }
+}
+
+impl fmt::Display for ScoreRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Poor => "poor",
+            Self::Fair => "fair",
+            Self::Good => "good",
+        })
+    }
+}
+
+impl AirData {
+    /// Classify this sample's CO2 reading.
+    pub fn co2_level(&self) -> Co2Level {
+        self.co2_level_with(&Thresholds::default())
+    }
+
+    /// Classify this sample's CO2 reading, with the given thresholds.
+    pub fn co2_level_with(&self, thresholds: &Thresholds) -> Co2Level {
+        Co2Level::classify(self.co2, &thresholds.co2)
+    }
+
+    /// Classify this sample's TVOC reading.
+    pub fn voc_level(&self) -> VocLevel {
+        self.voc_level_with(&Thresholds::default())
+    }
+
+    /// Classify this sample's TVOC reading, with the given thresholds.
+    pub fn voc_level_with(&self, thresholds: &Thresholds) -> VocLevel {
+        VocLevel::classify(self.voc, &thresholds.voc)
+    }
+
+    /// Classify this sample's PM2.5 reading, if the device reports one.
+    pub fn pm25_level(&self) -> Option<Pm25Level> {
+        self.pm25_level_with(&Thresholds::default())
+    }
+
+    /// Classify this sample's PM2.5 reading, if the device reports one, with
+    /// the given thresholds.
+    pub fn pm25_level_with(&self, thresholds: &Thresholds) -> Option<Pm25Level> {
+        self.pm25
+            .map(|pm25| Pm25Level::classify(pm25, &thresholds.pm25))
+    }
+
+    /// Classify this sample's Awair Score.
+    pub fn score_rating(&self) -> ScoreRating {
+        self.score_rating_with(&Thresholds::default())
+    }
+
+    /// Classify this sample's Awair Score, with the given thresholds.
+    pub fn score_rating_with(&self, thresholds: &Thresholds) -> ScoreRating {
+        ScoreRating::classify(self.score, &thresholds.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    fn sample() -> AirData {
+        serde_json::from_str(SAMPLE_AIR_DATA).unwrap()
+    }
+
+    #[test]
+    fn test_co2_level_boundaries() {
+        let mut data = sample();
+        for (co2, expected) in [
+            (0, Co2Level::Good),
+            (599, Co2Level::Good),
+            (600, Co2Level::Acceptable),
+            (999, Co2Level::Acceptable),
+            (1000, Co2Level::Poor),
+            (2499, Co2Level::Poor),
+            (2500, Co2Level::Dangerous),
+        ] {
+            data.co2 = co2;
+            assert_eq!(data.co2_level(), expected, "CO2 {co2}");
+        }
+    }
+
+    #[test]
+    fn test_voc_level_boundaries() {
+        let mut data = sample();
+        for (voc, expected) in [
+            (332, VocLevel::Good),
+            (333, VocLevel::Acceptable),
+            (999, VocLevel::Acceptable),
+            (1000, VocLevel::Poor),
+            (3332, VocLevel::Poor),
+            (3333, VocLevel::Dangerous),
+        ] {
+            data.voc = voc;
+            assert_eq!(data.voc_level(), expected, "VOC {voc}");
+        }
+    }
+
+    #[test]
+    fn test_pm25_level_boundaries() {
+        let mut data = sample();
+        for (pm25, expected) in [
+            (14, Pm25Level::Good),
+            (15, Pm25Level::Acceptable),
+            (34, Pm25Level::Acceptable),
+            (35, Pm25Level::Poor),
+            (54, Pm25Level::Poor),
+            (55, Pm25Level::Dangerous),
+        ] {
+            data.pm25 = Some(pm25);
+            assert_eq!(data.pm25_level(), Some(expected), "PM2.5 {pm25}");
+        }
+
+        data.pm25 = None;
+        assert_eq!(data.pm25_level(), None);
+    }
+
+    #[test]
+    fn test_score_rating_boundaries() {
+        let mut data = sample();
+        for (score, expected) in [
+            (0, ScoreRating::Poor),
+            (59, ScoreRating::Poor),
+            (60, ScoreRating::Fair),
+            (79, ScoreRating::Fair),
+            (80, ScoreRating::Good),
+            (100, ScoreRating::Good),
+        ] {
+            data.score = score;
+            assert_eq!(data.score_rating(), expected, "score {score}");
+        }
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let strict = Thresholds {
+            co2: [400, 800, 1200],
+            voc: [100, 300, 1000],
+            pm25: [5, 12, 35],
+            score: [70, 90],
+        };
+        assert_eq!(Thresholds::default().co2, CO2_THRESHOLDS);
+
+        let data = sample();
+        assert_eq!(data.co2_level(), Co2Level::Acceptable);
+        assert_eq!(data.co2_level_with(&strict), Co2Level::Acceptable);
+        assert_eq!(data.voc_level(), VocLevel::Good);
+        assert_eq!(data.voc_level_with(&strict), VocLevel::Acceptable);
+        assert_eq!(data.pm25_level(), Some(Pm25Level::Good));
+        assert_eq!(data.pm25_level_with(&strict), Some(Pm25Level::Good));
+        assert_eq!(data.score_rating(), ScoreRating::Good);
+        assert_eq!(data.score_rating_with(&strict), ScoreRating::Good);
+
+        let mut stuffy = sample();
+        stuffy.co2 = 900;
+        stuffy.score = 85;
+        assert_eq!(stuffy.co2_level(), Co2Level::Acceptable);
+        assert_eq!(stuffy.co2_level_with(&strict), Co2Level::Poor);
+        assert_eq!(stuffy.score_rating(), ScoreRating::Good);
+        assert_eq!(stuffy.score_rating_with(&strict), ScoreRating::Fair);
+    }
+
+    #[test]
+    fn test_thresholds_new() {
+        assert_eq!(
+            Thresholds::new(
+                CO2_THRESHOLDS,
+                VOC_THRESHOLDS,
+                PM25_THRESHOLDS,
+                SCORE_THRESHOLDS
+            )
+            .unwrap(),
+            Thresholds::default()
+        );
+
+        for (thresholds, field) in [
+            (
+                Thresholds::new([1000, 600, 2500], VOC_THRESHOLDS, PM25_THRESHOLDS, [60, 80]),
+                "CO2",
+            ),
+            (
+                Thresholds::new(CO2_THRESHOLDS, [333, 333, 3333], PM25_THRESHOLDS, [60, 80]),
+                "TVOC",
+            ),
+            (
+                Thresholds::new(CO2_THRESHOLDS, VOC_THRESHOLDS, [55, 35, 15], [60, 80]),
+                "PM2.5",
+            ),
+            (
+                Thresholds::new(CO2_THRESHOLDS, VOC_THRESHOLDS, PM25_THRESHOLDS, [80, 60]),
+                "score",
+            ),
+        ] {
+            assert!(
+                matches!(thresholds, Err(Error::InvalidThresholds(f)) if f == field),
+                "{field}"
+            );
+        }
+    }
+}
@@ -2,85 +2,908 @@
 //!
 //! The Awair Local API is documented here:
 //! <https://support.getawair.com/hc/en-us/articles/360049221014-Awair-Element-Local-API-Feature>
+//!
+//! # TLS
+//!
+//! TLS support is only needed for `https://` API bases, and is selected with
+//! one of two features:
+//!
+//! * `native-tls` (default): uses the platform's TLS library (OpenSSL on
+//!   Linux). This integrates with the system trust store, but requires
+//!   OpenSSL at build and run time, which can be awkward on musl or in
+//!   minimal containers.
+//! * `rustls-tls`: uses `rustls`, a pure-Rust TLS implementation with
+//!   bundled root certificates. This builds anywhere, but ignores the
+//!   system trust store.
+//!
+//! Enabling both features is deliberately allowed, rather than a compile
+//! error: Cargo features are additive, so rejecting the combination would
+//! break `--all-features` builds (including docs.rs), and any dependency
+//! graph where two crates pick different backends. In that case both
+//! backends are compiled in, and clients built with [`AwairBuilder`] use
+//! `rustls`. With neither, only `http://` bases can be used.
+//!
+//! # Compression
+//!
+//! With the `compression` feature, clients advertise support for `gzip` and
+//! `deflate` responses, and transparently decode them. The device itself
+//! doesn't compress its responses, but a proxy in front of it might.
 
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(missing_docs)]
 #![allow(clippy::redundant_field_names)]
 #![forbid(unsafe_code)]
 
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ops::ControlFlow;
+use std::str::FromStr;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use serde::{
+    de::{self, DeserializeOwned},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use thiserror::Error;
 
+use breaker::CircuitBreaker;
+
+mod aqi;
+mod breaker;
+mod comfort;
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "discovery")]
+mod discovery;
+#[cfg(feature = "hass")]
+pub mod hass;
+#[cfg(feature = "influx")]
+mod influx;
+#[cfg(feature = "jsonl")]
+mod jsonl;
+mod lenient;
+mod levels;
+mod multi;
+#[cfg(feature = "async")]
+mod nonblocking;
+mod partial;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+#[cfg(feature = "uom")]
+mod quantities;
+mod readings;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stats;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "chrono-tz")]
+mod tz;
+#[cfg(feature = "units")]
+pub mod units;
+mod ventilation;
+
+pub use macaddr::MacAddr6;
+
+pub use comfort::Comfort;
+#[cfg(feature = "csv")]
+pub use csv::write_csv;
+#[cfg(all(feature = "async", feature = "discovery"))]
+pub use discovery::discover_stream;
+#[cfg(feature = "discovery")]
+pub use discovery::{discover, SERVICE_TYPE};
+#[cfg(feature = "jsonl")]
+pub use jsonl::append_jsonl;
+pub use levels::{Co2Level, Pm25Level, ScoreRating, Thresholds, VocLevel};
+pub use multi::MultiAwair;
+#[cfg(feature = "async")]
+pub use multi::MultiAwairAsync;
+#[cfg(feature = "async")]
+pub use nonblocking::AwairAsync;
+pub use partial::FieldError;
+pub use readings::{Reading, SensorReading};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSink;
+pub use stats::{
+    average_across, dedup_by_timestamp, sort_by_timestamp, AirDataAverager, AirDataDelta, Anomaly,
+    AnomalyDetector, Baseline, BaselineMonitor, Ema, Extremes, SessionStats, StaleWatchdog,
+    WatchdogState,
+};
+pub use ventilation::Ventilation;
+
+/// The Local API endpoint for the latest air quality sample.
+const LATEST_PATH: &str = "/air-data/latest";
+
+/// The Local API endpoint for the latest uncalibrated air quality sample.
+const RAW_PATH: &str = "/air-data/raw";
+
+/// The Local API endpoint for the 5-minute average air quality sample.
+const FIVE_MIN_AVG_PATH: &str = "/air-data/5-min-avg";
+
+/// The Local API endpoint for the 15-minute average air quality sample.
+const FIFTEEN_MIN_AVG_PATH: &str = "/air-data/15-min-avg";
+
+/// The Local API endpoint for the device's configuration state.
+const CONFIG_PATH: &str = "/settings/config/data";
+
+/// The shortest interval between polls made by [`Awair::stream`].
+pub const MIN_STREAM_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Represents the errors that can occur when retrieving search results.
+///
+/// Errors from requests made by [`Awair`] and `AwairAsync` are wrapped in
+/// [`Error::Endpoint`], which records the endpoint that failed. To match on
+/// the underlying error, use [`Error::without_endpoint`] first:
+///
+/// ```
+/// # use awair_local_api::Error;
+/// fn is_unavailable(e: &Error) -> bool {
+///     matches!(e.without_endpoint(), Error::HttpStatus { status: 503, .. })
+/// }
+/// ```
 #[derive(Debug, Error)]
 pub enum Error {
     /// The URL requested by the user is valid, but unusable.
-    #[error("invalid API URL: cannot be a valid base")]
+    #[error("invalid API URL: {0} cannot be a valid base")]
     InvalidBase(String),
+    /// No API URLs were given to [`Awair::with_fallbacks`].
+    #[error("no API URLs given")]
+    NoBases,
+    /// The URL requested by the user is valid, but doesn't use HTTP or HTTPS.
+    #[error("invalid API URL: {0} must use http or https")]
+    UnsupportedScheme(String),
     /// An API URL is invalid.
     #[error("invalid API URL")]
     InvalidUrl(#[from] url::ParseError),
-    /// An request error occurred.
+    /// A request timed out, either while connecting or while waiting on a response.
+    ///
+    /// The underlying error is absent when the request exceeded the client's
+    /// [hard timeout](AwairBuilder::hard_timeout).
+    #[error("request timed out")]
+    Timeout(#[source] Option<reqwest::Error>),
+    /// A connection to the device couldn't be established.
+    #[error("connection error")]
+    Connect(#[source] reqwest::Error),
+    /// Any other request error occurred.
     #[error("request error")]
-    Request(#[from] reqwest::Error),
+    Request(#[source] reqwest::Error),
+    /// The device responded with an unsuccessful (non-2xx) HTTP status.
+    #[error("device responded with HTTP {status}: {body}")]
+    HttpStatus {
+        /// The response's HTTP status code.
+        status: u16,
+        /// The response's body, which may be empty.
+        body: String,
+    },
+    /// One of the device's reported network addresses couldn't be parsed.
+    #[error("invalid {field}: {value:?}")]
+    InvalidAddress {
+        /// The name of the field being parsed.
+        field: &'static str,
+        /// The field's unparseable value.
+        value: String,
+    },
+    /// An environment variable needed to configure a client couldn't be read.
+    #[error("couldn't read {var} from the environment")]
+    Env {
+        /// The name of the environment variable.
+        var: &'static str,
+        /// The underlying error.
+        #[source]
+        source: std::env::VarError,
+    },
+    /// An environment variable needed to configure a client is set, but invalid.
+    #[error("invalid {var}: {value:?}")]
+    InvalidEnv {
+        /// The name of the environment variable.
+        var: &'static str,
+        /// The variable's invalid value.
+        value: String,
+    },
+    /// The device responded with something other than JSON, e.g. the HTML
+    /// page served while it's being set up.
+    #[error("expected a JSON response, but got {content_type}; is the device in Local API mode?")]
+    UnexpectedContentType {
+        /// The response's content type.
+        content_type: String,
+    },
+    /// The client's circuit breaker is open, so the device wasn't contacted.
+    #[error("circuit breaker is open after repeated failures")]
+    CircuitOpen,
+    /// A request to one of the device's endpoints failed.
+    #[error("request to {path} failed: {source}")]
+    Endpoint {
+        /// The endpoint's path, e.g. `/air-data/latest`.
+        path: String,
+        /// The underlying error.
+        #[source]
+        source: Box<Error>,
+    },
+    /// The device's firmware is known to be too old for the requested endpoint,
+    /// so the device wasn't contacted.
+    #[error("{endpoint} requires firmware {required} or newer")]
+    UnsupportedByFirmware {
+        /// The endpoint's path.
+        endpoint: String,
+        /// The minimum firmware version that serves the endpoint.
+        required: semver::Version,
+    },
+    /// The device's latest sample didn't change while estimating its update
+    /// interval (see [`Awair::estimate_update_interval`]).
+    #[error("device's latest sample didn't change within {0:?}")]
+    NoUpdate(Duration),
+    /// An LED brightness outside of [`LedConfig::MIN_BRIGHTNESS`] to
+    /// [`LedConfig::MAX_BRIGHTNESS`] was requested.
+    #[error("LED brightness {0} is out of range")]
+    InvalidBrightness(u32),
+    /// The device's reported firmware version couldn't be parsed.
+    #[error("invalid firmware version: {0:?}")]
+    VersionParse(String),
+    /// Some classification thresholds given to [`Thresholds::new`] aren't in
+    /// ascending order.
+    #[error("{0} thresholds must be strictly ascending")]
+    InvalidThresholds(&'static str),
+    /// An error occurred while discovering devices over mDNS.
+    #[cfg(feature = "discovery")]
+    #[error("mDNS discovery error")]
+    Discovery(#[from] mdns_sd::Error),
+    /// An error occurred while persisting samples to SQLite.
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error")]
+    Sqlite(#[from] rusqlite::Error),
+    /// The device's configured timezone isn't a known TZ database name.
+    #[cfg(feature = "chrono-tz")]
+    #[error("invalid timezone: {0}")]
+    InvalidTimezone(String),
+    /// A `reqwest-middleware` middleware failed a request (see
+    /// `AwairAsync::with_middleware_client`).
+    #[cfg(feature = "middleware")]
+    #[error("middleware error")]
+    Middleware(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Returns whether this error is transient, i.e. whether the failed
+    /// request might succeed if retried.
+    ///
+    /// Timeouts and connection errors are considered transient.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.without_endpoint(),
+            Error::Timeout(_) | Error::Connect(_)
+        )
+    }
+
+    /// Returns the path of the endpoint that this error came from, if any.
+    pub fn endpoint(&self) -> Option<&str> {
+        match self {
+            Error::Endpoint { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Returns this error without its [`Error::Endpoint`] context, if any.
+    pub fn without_endpoint(&self) -> &Error {
+        match self {
+            Error::Endpoint { source, .. } => source.without_endpoint(),
+            e => e,
+        }
+    }
+
+    /// Attach the given endpoint's path to this error.
+    fn at(self, path: &str) -> Self {
+        Error::Endpoint {
+            path: path.into(),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        // NOTE: Connection timeouts are both timeouts and connection errors;
+        // we classify them as timeouts.
+        if err.is_timeout() {
+            Error::Timeout(Some(err))
+        } else if err.is_connect() {
+            Error::Connect(err)
+        } else {
+            Error::Request(err)
+        }
+    }
+}
+
+#[cfg(feature = "middleware")]
+impl From<reqwest_middleware::Error> for Error {
+    fn from(err: reqwest_middleware::Error) -> Self {
+        match err {
+            reqwest_middleware::Error::Reqwest(err) => err.into(),
+            reqwest_middleware::Error::Middleware(err) => Error::Middleware(err.into()),
+        }
+    }
 }
 
 /// Represents a sample of air quality data taken from an Awair
 /// device's Local API.
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// Not every Awair model has every sensor: the VOC sensor's estimated CO2
+/// fields, the PM fields, and the Omni's light and sound fields are
+/// model-dependent, and are `None` when the device doesn't report them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AirData {
     /// The time reported by the device's internal clock.
     pub timestamp: DateTime<Utc>,
     /// The Awair Score, from 0-100.
+    ///
+    /// Scores outside of this range are rejected during deserialization.
+    #[serde(deserialize_with = "deserialize_score")]
     pub score: u8,
     /// The dew point, in degrees Celsius.
+    #[serde(deserialize_with = "lenient::number")]
     pub dew_point: f32,
     /// The dry bulb temperature, in degrees Celsius.
-    #[serde(rename = "temp")]
+    #[serde(rename = "temp", deserialize_with = "lenient::number")]
     pub temperature: f32,
     /// The relative humidity, as a percent.
-    #[serde(rename = "humid")]
+    #[serde(rename = "humid", deserialize_with = "lenient::number")]
     pub humidity: f32,
-    /// The absolute humidity, as a percent.
-    #[serde(rename = "abs_humid")]
+    /// The absolute humidity, in grams per cubic meter.
+    #[serde(rename = "abs_humid", deserialize_with = "lenient::number")]
     pub absolute_humidity: f32,
     /// The CO2 reading, in parts per million.
+    #[serde(deserialize_with = "lenient::number")]
     pub co2: u32,
-    #[serde(rename = "co2_est")]
     /// The VOC sensor's estimated CO2 reading, in parts per million.
-    pub estimated_co2: u32,
+    ///
+    /// This is model-dependent.
+    #[serde(
+        rename = "co2_est",
+        default,
+        deserialize_with = "lenient::optional_number"
+    )]
+    pub estimated_co2: Option<u32>,
     /// The VOC sensor's CO2 baseline (unitless).
-    #[serde(rename = "co2_est_baseline")]
-    pub estimated_co2_baseline: u32,
+    ///
+    /// This is model-dependent.
+    #[serde(
+        rename = "co2_est_baseline",
+        default,
+        deserialize_with = "lenient::optional_number"
+    )]
+    pub estimated_co2_baseline: Option<u32>,
     /// The TVOC reading, in parts per billion.
+    #[serde(deserialize_with = "lenient::number")]
     pub voc: u32,
     /// The TVOC sensor's VOC baseline (unitless).
+    #[serde(deserialize_with = "lenient::number")]
     pub voc_baseline: u32,
     /// The TVOC sensor's H2 (hydrogen gas) reading (unitless).
+    #[serde(deserialize_with = "lenient::number")]
     pub voc_h2_raw: u32,
     /// The TVOC sensor's ethanol gas reading (unitless).
+    #[serde(deserialize_with = "lenient::number")]
     pub voc_ethanol_raw: u32,
     /// The PM2.5 reading (in microns per cubic meter)
-    pub pm25: u32,
+    ///
+    /// This is model-dependent.
+    #[serde(default, deserialize_with = "lenient::optional_number")]
+    pub pm25: Option<u32>,
     /// The PM10 reading (in microns per cubic meter)
-    #[serde(rename = "pm10_est")]
-    pub estimated_pm10: u32,
+    ///
+    /// This is model-dependent.
+    #[serde(
+        rename = "pm10_est",
+        default,
+        deserialize_with = "lenient::optional_number"
+    )]
+    pub estimated_pm10: Option<u32>,
+    /// The illuminance, in lux.
+    ///
+    /// This is model-dependent, and is reported by the Awair Omni.
+    #[serde(default, deserialize_with = "lenient::optional_number")]
+    pub lux: Option<f32>,
+    /// The A-weighted sound pressure level, in decibels (dBA).
+    ///
+    /// This is model-dependent, and is reported by the Awair Omni.
+    #[serde(default, deserialize_with = "lenient::optional_number")]
+    pub spl_a: Option<f32>,
+    /// The per-factor contributions to the Awair Score.
+    ///
+    /// This is only reported by some firmware, and is `None` otherwise.
+    #[serde(default)]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    /// Any fields reported by the device that aren't otherwise modeled,
+    /// e.g. those added by newer firmware.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// The contribution of each factor to an Awair Score, as reported by the device.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// The temperature's contribution.
+    #[serde(rename = "temp", deserialize_with = "lenient::number")]
+    pub temperature: f32,
+    /// The relative humidity's contribution.
+    #[serde(rename = "humid", deserialize_with = "lenient::number")]
+    pub humidity: f32,
+    /// The CO2 reading's contribution.
+    #[serde(deserialize_with = "lenient::number")]
+    pub co2: f32,
+    /// The VOC reading's contribution.
+    #[serde(deserialize_with = "lenient::number")]
+    pub voc: f32,
+    /// The PM2.5 reading's contribution.
+    ///
+    /// This is model-dependent.
+    #[serde(default, deserialize_with = "lenient::optional_number")]
+    pub pm25: Option<f32>,
+}
+
+/// An air quality sample, labeled with the device that reported it.
+///
+/// This is returned by [`Awair::poll_labeled`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabeledAirData {
+    /// The reporting device's ID (see [`DeviceConfig::device_id`]).
+    pub device_id: String,
+    /// The air quality sample.
+    pub data: AirData,
+}
+
+impl PartialOrd for AirData {
+    /// Samples are ordered by their timestamps.
+    ///
+    /// Distinct samples that share a timestamp are incomparable, keeping this
+    /// ordering consistent with `PartialEq`.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.timestamp.cmp(&other.timestamp) {
+            Ordering::Equal => (self == other).then_some(Ordering::Equal),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+/// The highest valid Awair Score.
+const MAX_SCORE: u8 = 100;
+
+/// The highest CO2 reading (in parts per million) within the Awair's rated
+/// sensor range.
+const MAX_CO2: u32 = 5000;
+
+/// The highest TVOC reading (in parts per billion) within the Awair's rated
+/// sensor range.
+const MAX_VOC: u32 = 60_000;
+
+/// Deserialize an Awair Score, rejecting any value outside of 0-100.
+fn deserialize_score<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let score: i64 = lenient::number(deserializer)?;
+
+    match u8::try_from(score) {
+        Ok(score) if score <= MAX_SCORE => Ok(score),
+        _ => Err(de::Error::invalid_value(
+            de::Unexpected::Signed(score),
+            &"an Awair Score from 0-100",
+        )),
+    }
+}
+
+/// Widen a reading to an `f64`, preserving its shortest decimal
+/// representation (e.g. `21.4` rather than `21.399999618530273`).
+fn widen(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(value.into())
+}
+
+/// Convert a temperature in degrees Celsius to degrees Fahrenheit.
+fn celsius_to_fahrenheit(celsius: f32) -> f32 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+impl AirData {
+    /// The time reported by the device's internal clock, in the given timezone.
+    ///
+    /// With the `chrono-tz` feature, [`DeviceConfig::timezone`] can be used to
+    /// present this in the device's own local time.
+    pub fn timestamp_local<Tz: TimeZone>(&self, tz: &Tz) -> DateTime<Tz> {
+        self.timestamp.with_timezone(tz)
+    }
+
+    /// The age of this sample as of `now`, according to its timestamp.
+    ///
+    /// The timestamp comes from the device's internal clock: if that clock is
+    /// ahead of `now`, the returned age is negative.
+    pub fn age(&self, now: DateTime<Utc>) -> chrono::Duration {
+        now - self.timestamp
+    }
+
+    /// The difference between this sample's timestamp and `reference`,
+    /// which is positive if the device's clock is ahead.
+    ///
+    /// For a freshly polled sample and an accurate `reference` (e.g. the
+    /// system time), this approximates the device's clock skew.
+    pub fn clock_skew(&self, reference: DateTime<Utc>) -> chrono::Duration {
+        self.timestamp - reference
+    }
+
+    /// Returns whether this sample is older than `max_age` as of `now`.
+    ///
+    /// Samples timestamped in the future (see [`AirData::age`]) are never
+    /// stale, for any non-negative `max_age`.
+    pub fn is_stale(&self, now: DateTime<Utc>, max_age: chrono::Duration) -> bool {
+        self.age(now) > max_age
+    }
+
+    /// Returns whether this sample looks like it was taken while the device's
+    /// gas sensors were still warming up, e.g. shortly after power-on.
+    ///
+    /// This is a heuristic: a sample is flagged if either sensor reports a
+    /// baseline of zero, if the CO2 reading is zero, or if the CO2 or TVOC
+    /// reading exceeds the sensor's rated range (5000 ppm and 60,000 ppb,
+    /// respectively). A sensor that's failed outright looks the same, so a
+    /// device that never stops "warming up" likely needs attention.
+    pub fn is_warming_up(&self) -> bool {
+        self.voc_baseline == 0
+            || self.estimated_co2_baseline == Some(0)
+            || self.co2 == 0
+            || self.co2 > MAX_CO2
+            || self.voc > MAX_VOC
+    }
+
+    /// The dry bulb temperature, in degrees Fahrenheit.
+    pub fn temperature_fahrenheit(&self) -> f32 {
+        celsius_to_fahrenheit(self.temperature)
+    }
+
+    /// The dry bulb temperature, in Kelvin.
+    pub fn temperature_kelvin(&self) -> f32 {
+        self.temperature + 273.15
+    }
+
+    /// The dew point, in degrees Fahrenheit.
+    pub fn dew_point_fahrenheit(&self) -> f32 {
+        celsius_to_fahrenheit(self.dew_point)
+    }
+
+    /// The TVOC reading as a mass concentration, in micrograms per cubic
+    /// meter, for a VOC mixture with the given average molar mass (in grams
+    /// per mole) at this sample's temperature.
+    ///
+    /// See [`AirData::voc_ug_per_m3_at`] for the assumptions made.
+    pub fn voc_ug_per_m3(&self, molar_mass: f32) -> f32 {
+        self.voc_ug_per_m3_at(molar_mass, self.temperature)
+    }
+
+    /// Like [`AirData::voc_ug_per_m3`], but at the given temperature (in
+    /// degrees Celsius) rather than this sample's.
+    ///
+    /// This treats the air as an ideal gas at standard atmospheric pressure
+    /// (1 atm), so it's only approximate at altitude. No single molar mass is
+    /// correct for a mixture of VOCs, so reports usually pick a reference
+    /// compound, e.g. toluene (92.14 g/mol).
+    pub fn voc_ug_per_m3_at(&self, molar_mass: f32, temperature: f32) -> f32 {
+        // The ideal gas constant, in L·atm/(mol·K).
+        const GAS_CONSTANT: f32 = 0.082_057_37;
+
+        let molar_volume = GAS_CONSTANT * (temperature + 273.15);
+        self.voc as f32 * molar_mass / molar_volume
+    }
+
+    /// Returns this sample as a flat map, with one entry for each reported
+    /// sensor and an RFC 3339 `timestamp`.
+    ///
+    /// The keys are the same as in this sample's serialized form. The
+    /// [score breakdown](AirData::score_breakdown) and any unmodeled fields
+    /// are omitted.
+    ///
+    /// This fails only if the sample can't be serialized as JSON.
+    pub fn to_flat_map(
+        &self,
+    ) -> Result<serde_json::Map<String, serde_json::Value>, serde_json::Error> {
+        // NOTE: We round-trip through JSON text, rather than using
+        // `serde_json::to_value`, so that `f32`s aren't widened noisily.
+        let mut map: serde_json::Map<_, _> = serde_json::from_slice(&serde_json::to_vec(self)?)?;
+
+        map.retain(|key, value| {
+            !(value.is_null() || value.is_object() || self.extra.contains_key(key))
+        });
+        Ok(map)
+    }
+}
+
+impl fmt::Display for AirData {
+    /// Formats a compact, human-readable summary of the sample's main readings,
+    /// e.g. `Score: 92  Temp: 21.4°C  Humidity: 43%  CO2: 512ppm  VOC: 120ppb  PM2.5: 3µg/m³`.
+    ///
+    /// PM2.5 is omitted if the device doesn't report it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Score: {}  Temp: {:.1}°C  Humidity: {:.0}%  CO2: {}ppm  VOC: {}ppb",
+            self.score, self.temperature, self.humidity, self.co2, self.voc
+        )?;
+
+        if let Some(pm25) = self.pm25 {
+            write!(f, "  PM2.5: {pm25}µg/m³")?;
+        }
+
+        Ok(())
+    }
 }
 
 /// The Awair device's LED configuration state, as returned from
 /// the Local API.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LedConfig {
     /// The LED's operating mode.
-    pub mode: String,
-    /// The LED's brightness (unknown units).
+    pub mode: LedMode,
+    /// The LED's brightness, as a percentage from [`LedConfig::MIN_BRIGHTNESS`]
+    /// to [`LedConfig::MAX_BRIGHTNESS`].
     pub brightness: u32,
 }
 
+impl LedConfig {
+    /// The minimum LED brightness accepted by the Local API.
+    ///
+    /// NOTE: Neither this nor [`LedConfig::MAX_BRIGHTNESS`] has been confirmed
+    /// empirically, and the Local API's documentation doesn't give a range:
+    /// 0 to 100 is an assumption, based on the brightness being a percentage.
+    pub const MIN_BRIGHTNESS: u32 = 0;
+
+    /// The maximum LED brightness accepted by the Local API.
+    pub const MAX_BRIGHTNESS: u32 = 100;
+
+    /// Returns an [`Error::InvalidBrightness`] if the given brightness is
+    /// outside of [`LedConfig::MIN_BRIGHTNESS`] to [`LedConfig::MAX_BRIGHTNESS`].
+    fn check_brightness(brightness: u32) -> Result<(), Error> {
+        if !(Self::MIN_BRIGHTNESS..=Self::MAX_BRIGHTNESS).contains(&brightness) {
+            return Err(Error::InvalidBrightness(brightness));
+        }
+
+        Ok(())
+    }
+
+    /// The LED's brightness, normalized to a percentage from 0 to 100.
+    ///
+    /// Out-of-range brightnesses reported by the device are clamped. Under the
+    /// assumed range (see [`LedConfig::MIN_BRIGHTNESS`]), this is otherwise
+    /// just the brightness itself.
+    pub fn brightness_percent(&self) -> f32 {
+        let range = (Self::MAX_BRIGHTNESS - Self::MIN_BRIGHTNESS) as f32;
+        let brightness = self
+            .brightness
+            .clamp(Self::MIN_BRIGHTNESS, Self::MAX_BRIGHTNESS);
+
+        (brightness - Self::MIN_BRIGHTNESS) as f32 / range * 100.0
+    }
+}
+
+/// The modes available for an Awair device's display.
+///
+/// Modes that aren't otherwise modeled (e.g. those added by newer firmware)
+/// are preserved as [`DisplayMode::Unknown`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum DisplayMode {
+    /// Display the Awair Score.
+    Score,
+    /// Display the temperature.
+    Temperature,
+    /// Display the relative humidity.
+    Humidity,
+    /// Display the CO2 reading.
+    Co2,
+    /// Display the TVOC reading.
+    Voc,
+    /// Display the PM2.5 reading.
+    Pm25,
+    /// Display the time.
+    Clock,
+    /// A display mode not known to this crate, as reported by the device.
+    Unknown(String),
+}
+
+impl DisplayMode {
+    /// Returns the Local API's name for this display mode.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Score => "score",
+            Self::Temperature => "temp",
+            Self::Humidity => "humid",
+            Self::Co2 => "co2",
+            Self::Voc => "voc",
+            Self::Pm25 => "pm25",
+            Self::Clock => "clock",
+            Self::Unknown(mode) => mode,
+        }
+    }
+}
+
+impl From<String> for DisplayMode {
+    fn from(mode: String) -> Self {
+        match mode.as_str() {
+            "score" => Self::Score,
+            "temp" => Self::Temperature,
+            "humid" => Self::Humidity,
+            "co2" => Self::Co2,
+            "voc" => Self::Voc,
+            "pm25" => Self::Pm25,
+            "clock" => Self::Clock,
+            _ => Self::Unknown(mode),
+        }
+    }
+}
+
+impl From<DisplayMode> for String {
+    fn from(mode: DisplayMode) -> Self {
+        match mode {
+            DisplayMode::Unknown(mode) => mode,
+            mode => mode.as_str().into(),
+        }
+    }
+}
+
+impl fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The body of a display mode update.
+#[derive(Serialize)]
+struct DisplayUpdate {
+    display: DisplayMode,
+}
+
+/// The operating modes available for an Awair device's LED.
+///
+/// Modes that aren't otherwise modeled are preserved as [`LedMode::Unknown`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum LedMode {
+    /// Adjust the LED's brightness automatically.
+    Auto,
+    /// Use a fixed, user-supplied brightness.
+    Manual,
+    /// Dim the LED for sleeping.
+    Sleep,
+    /// An LED mode not known to this crate, as reported by the device.
+    Unknown(String),
+}
+
+impl LedMode {
+    /// Returns the Local API's name for this LED mode.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+            Self::Sleep => "sleep",
+            Self::Unknown(mode) => mode,
+        }
+    }
+}
+
+impl From<String> for LedMode {
+    fn from(mode: String) -> Self {
+        match mode.as_str() {
+            "auto" => Self::Auto,
+            "manual" => Self::Manual,
+            "sleep" => Self::Sleep,
+            _ => Self::Unknown(mode),
+        }
+    }
+}
+
+impl From<LedMode> for String {
+    fn from(mode: LedMode) -> Self {
+        match mode {
+            LedMode::Unknown(mode) => mode,
+            mode => mode.as_str().into(),
+        }
+    }
+}
+
+impl fmt::Display for LedMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The body of an LED configuration update.
+#[derive(Serialize)]
+struct LedUpdate {
+    led: LedSettings,
+}
+
+/// The LED settings within an [`LedUpdate`].
+#[derive(Serialize)]
+struct LedSettings {
+    mode: LedMode,
+    brightness: u8,
+}
+
+impl LedUpdate {
+    fn new(mode: LedMode, brightness: u8) -> Result<Self, Error> {
+        LedConfig::check_brightness(brightness.into())?;
+
+        Ok(Self {
+            led: LedSettings { mode, brightness },
+        })
+    }
+}
+
+/// The body of a knocking (knock-to-wake) update.
+#[derive(Serialize)]
+struct KnockingUpdate {
+    #[serde(serialize_with = "serialize_knocking")]
+    knocking: bool,
+}
+
+/// Returns the Local API's name for a knocking state.
+fn knocking_str(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Serialize a knocking state the way the Local API expects it: as an
+/// `"on"` or `"off"` string, rather than as a JSON boolean.
+fn serialize_knocking<S: Serializer>(enabled: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(knocking_str(*enabled))
+}
+
+/// Serialize an optional knocking state, as in [`serialize_knocking`].
+fn serialize_optional_knocking<S: Serializer>(
+    enabled: &Option<bool>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match enabled {
+        Some(enabled) => serialize_knocking(enabled, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Deserialize an optional knocking state, which is either an `"on"` or
+/// `"off"` string, a JSON boolean, or a `0` or `1`.
+///
+/// Any other value is treated as unknown, rather than failing the whole
+/// configuration's deserialization.
+fn deserialize_optional_knocking<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    let state = Option::<serde_json::Value>::deserialize(deserializer)?;
+
+    Ok(match state {
+        Some(serde_json::Value::Bool(enabled)) => Some(enabled),
+        Some(serde_json::Value::String(state)) => match state.as_str() {
+            "on" => Some(true),
+            "off" => Some(false),
+            _ => None,
+        },
+        Some(serde_json::Value::Number(state)) => match state.as_u64() {
+            Some(1) => Some(true),
+            Some(0) => Some(false),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
 /// Represents a Awair device's active configuration, as
 /// returned from the Local API.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DeviceConfig {
     /// The Awair device's ID.
     ///
@@ -105,55 +928,2969 @@ pub struct DeviceConfig {
     /// The Awair's configured timezone, as a TZ database name.
     pub timezone: String,
     /// The Awair's current display mode.
-    pub display: String,
+    pub display: DisplayMode,
     /// The Awair's current LED configuration.
     pub led: LedConfig,
+    /// Whether the Awair's knock-to-wake display feature is enabled, if the
+    /// device reports it.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_optional_knocking",
+        deserialize_with = "deserialize_optional_knocking"
+    )]
+    pub knocking: Option<bool>,
     /// (Presumably) the TVOC sensor's feature set (unknown format).
     pub voc_feature_set: u32,
+    /// Any fields reported by the device that aren't otherwise modeled,
+    /// e.g. those added by newer firmware.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Parse one of a [`DeviceConfig`]'s address fields.
+fn parse_address<T: FromStr>(field: &'static str, value: &str) -> Result<T, Error> {
+    value.parse().map_err(|_| Error::InvalidAddress {
+        field,
+        value: value.into(),
+    })
+}
+
+/// The minimum firmware version known to serve each endpoint. Endpoints that
+/// aren't listed have no known minimum.
+///
+/// NOTE: The averaged endpoints are listed at 1.2.4, the oldest firmware that
+/// this crate supports (see the README's support matrix), so that older
+/// firmware fails up front instead of with a confusing 404. Users who know
+/// their devices' minimums can override these with
+/// [`AwairBuilder::min_firmware`].
+const MIN_FIRMWARE: &[(&str, semver::Version)] = &[
+    (FIVE_MIN_AVG_PATH, semver::Version::new(1, 2, 4)),
+    (FIFTEEN_MIN_AVG_PATH, semver::Version::new(1, 2, 4)),
+];
+
+/// Returns the default table of minimum firmware versions, as owned entries.
+fn default_min_firmware() -> Vec<(String, semver::Version)> {
+    MIN_FIRMWARE
+        .iter()
+        .map(|(endpoint, required)| ((*endpoint).into(), required.clone()))
+        .collect()
+}
+
+/// Reject a request to the given endpoint if the device's firmware version
+/// (if known) is older than `table` says the endpoint requires.
+fn check_firmware<S: AsRef<str>>(
+    path: &str,
+    firmware: &Mutex<Option<semver::Version>>,
+    table: &[(S, semver::Version)],
+) -> Result<(), Error> {
+    let firmware = firmware.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(firmware) = &*firmware else {
+        return Ok(());
+    };
+
+    match table.iter().find(|(endpoint, _)| endpoint.as_ref() == path) {
+        Some((endpoint, required)) if firmware < required => Err(Error::UnsupportedByFirmware {
+            endpoint: endpoint.as_ref().into(),
+            required: required.clone(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Remember the firmware version reported in the given configuration.
+fn remember_firmware(config: &DeviceConfig, firmware: &Mutex<Option<semver::Version>>) {
+    if let Ok(version) = config.firmware_version() {
+        *firmware.lock().unwrap_or_else(PoisonError::into_inner) = Some(version);
+    }
+}
+
+/// Returns the cached device ID, if any.
+fn cached_device_id(device_id: &Mutex<Option<String>>) -> Option<String> {
+    device_id
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Cache the given device ID.
+fn cache_device_id(device_id: &Mutex<Option<String>>, id: &str) {
+    *device_id.lock().unwrap_or_else(PoisonError::into_inner) = Some(id.into());
+}
+
+/// Parse a firmware version leniently, padding out any missing minor or
+/// patch components.
+fn parse_firmware_version(version: &str) -> Result<semver::Version, Error> {
+    let version = version.trim();
+    let (core, suffix) = version
+        .find(['-', '+'])
+        .map_or((version, ""), |idx| version.split_at(idx));
+
+    let padding = match core.split('.').count() {
+        1 => ".0.0",
+        2 => ".0",
+        _ => "",
+    };
+
+    semver::Version::parse(&format!("{core}{padding}{suffix}"))
+        .map_err(|_| Error::VersionParse(version.into()))
+}
+
+impl DeviceConfig {
+    /// Parse the Awair's IP address.
+    pub fn ip(&self) -> Result<Ipv4Addr, Error> {
+        parse_address("ip", &self.ip)
+    }
+
+    /// Parse the network's mask.
+    pub fn netmask(&self) -> Result<Ipv4Addr, Error> {
+        parse_address("netmask", &self.netmask)
+    }
+
+    /// Parse the network's gateway IP address.
+    pub fn gateway(&self) -> Result<Ipv4Addr, Error> {
+        parse_address("gateway", &self.gateway)
+    }
+
+    /// Parse the MAC address of the WiFi network that the Awair is connected to.
+    pub fn wifi_mac(&self) -> Result<MacAddr6, Error> {
+        parse_address("wifi_mac", &self.wifi_mac)
+    }
+
+    /// Parse the Awair's active firmware version.
+    ///
+    /// Awair firmware versions aren't always full semantic versions, so
+    /// versions with only one or two components (like `1.2`) are accepted,
+    /// with the missing components treated as zero.
+    pub fn firmware_version(&self) -> Result<semver::Version, Error> {
+        parse_firmware_version(&self.firmware_version)
+    }
+
+    /// Returns this configuration's writable settings (the display mode, the
+    /// LED configuration, and the knocking state, if known) as a settings
+    /// update body, as accepted by [`Awair::apply_config`].
+    ///
+    /// Read-only fields, like the device's ID and network state, are omitted.
+    /// An out-of-range LED brightness is an [`Error::InvalidBrightness`], as
+    /// with [`Awair::set_led`].
+    pub fn to_update_body(&self) -> Result<serde_json::Value, Error> {
+        LedConfig::check_brightness(self.led.brightness)?;
+
+        let mut body = serde_json::json!({
+            "display": self.display,
+            "led": self.led,
+        });
+        if let Some(knocking) = self.knocking {
+            body["knocking"] = knocking_str(knocking).into();
+        }
+
+        Ok(body)
+    }
+}
+
+/// The optional Local API endpoints supported by an Awair device, as
+/// returned by [`Awair::capabilities`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether the device supports [`Awair::poll_raw`].
+    pub raw: bool,
+    /// Whether the device supports [`Awair::five_minute_average`].
+    pub five_min_avg: bool,
+    /// Whether the device supports [`Awair::fifteen_minute_average`].
+    pub fifteen_min_avg: bool,
+    /// Whether the device supports [`Awair::config`] and the configuration setters.
+    pub settings: bool,
+}
+
+/// The default total timeout for requests made by [`Awair::new`].
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default base delay between retries, for clients configured with
+/// [`AwairBuilder::retries`].
+pub const DEFAULT_BACKOFF: Duration = Duration::from_millis(250);
+
+/// The delay between polls made by [`Awair::estimate_update_interval`].
+const ESTIMATE_POLL_GAP: Duration = Duration::from_secs(1);
+
+/// How long [`Awair::estimate_update_interval`] waits for the device's latest
+/// sample to change before giving up.
+const ESTIMATE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The default `User-Agent` header sent by this crate's clients.
+pub const DEFAULT_USER_AGENT: &str = concat!("awair-local-api-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Compute the delay before the given retry attempt (counting from zero):
+/// the base delay, doubled for each previous attempt, plus up to 20% jitter.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    let delay = base.saturating_mul(2u32.saturating_pow(attempt));
+
+    // We don't need high-quality randomness for jitter, just enough to keep
+    // multiple clients from retrying in lockstep.
+    let random = RandomState::new().build_hasher().finish();
+    let jitter = delay.mul_f64((random % 1000) as f64 / 5000.0);
+
+    delay.saturating_add(jitter)
+}
+
+/// Build a Local API base URL from a host (a domain name or IP address) and port.
+fn host_port_base(host: &str, port: u16) -> String {
+    // IPv6 literals must be bracketed within URLs.
+    match host.parse::<Ipv6Addr>() {
+        Ok(_) => format!("http://[{host}]:{port}"),
+        Err(_) => format!("http://{host}:{port}"),
+    }
+}
+
+/// Turn a user-supplied address into a Local API base URL: a full URL is used
+/// as given, and `http://` is prepended to anything else (an IP address, or a
+/// host with an optional port and path).
+fn loose_base(input: &str) -> String {
+    let input = input.trim();
+    if input.contains("://") {
+        return input.into();
+    }
+
+    // NOTE: Bare IPv6 literals need brackets, which `host_port_base` adds.
+    match input.parse::<Ipv6Addr>() {
+        Ok(_) => host_port_base(input, 80),
+        Err(_) => format!("http://{input}"),
+    }
+}
+
+/// The device's update interval, if `next` is a newer sample than `first`.
+fn update_interval(first: &AirData, next: &AirData) -> Option<Duration> {
+    (next.timestamp - first.timestamp)
+        .to_std()
+        .ok()
+        .filter(|interval| !interval.is_zero())
+}
+
+/// Parse and validate a user-supplied Local API base URL.
+fn parse_base(api_base: &str) -> Result<url::Url, Error> {
+    let mut url = url::Url::parse(api_base)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::UnsupportedScheme(api_base.into()));
+    }
+
+    if url.cannot_be_a_base() {
+        return Err(Error::InvalidBase(api_base.into()));
+    }
+
+    // Ensure that the base's path is treated as a directory, so that
+    // endpoints are joined beneath it rather than replacing its last component.
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+
+    Ok(url)
+}
+
+/// Join an endpoint's path onto a Local API base URL.
+///
+/// Endpoint paths are joined relative to the base, so that any path prefix
+/// on the base (e.g. from a reverse proxy) is preserved.
+fn endpoint_url(api_base: &url::Url, path: &str) -> Result<url::Url, Error> {
+    Ok(api_base.join(path.trim_start_matches('/'))?)
 }
 
 /// Represents a connection to an Awair device.
-#[derive(Debug)]
+///
+/// Clients are cheap to clone, e.g. for use across threads: clones share the
+/// underlying connection pool, as well as any rate limiting, caching, and
+/// circuit breaker state.
+#[derive(Clone, Debug)]
 pub struct Awair {
     api_base: url::Url,
     http: reqwest::blocking::Client,
+    retries: u32,
+    backoff: Duration,
+    min_interval: Option<Duration>,
+    last_poll: Arc<Mutex<Option<Instant>>>,
+    cache_ttl: Option<Duration>,
+    cache: Arc<Mutex<Option<(Instant, AirData)>>>,
+    breaker: Option<Arc<CircuitBreaker>>,
+    fallbacks: Arc<[url::Url]>,
+    preferred: Arc<AtomicUsize>,
+    hard_timeout: Option<Duration>,
+    firmware: Arc<Mutex<Option<semver::Version>>>,
+    min_firmware: Arc<[(String, semver::Version)]>,
+    latest_path: String,
+    config_path: String,
+    device_id: Arc<Mutex<Option<String>>>,
+    #[cfg(feature = "chrono-tz")]
+    timezone: Arc<Mutex<Option<chrono_tz::Tz>>>,
 }
 
 impl Awair {
     /// Create a new client capable of talking to an Awair's Local API.
+    ///
+    /// The client uses a total request timeout of [`DEFAULT_TIMEOUT`];
+    /// use [`Awair::builder`] for more control over the client's configuration.
     pub fn new(api_base: &str) -> Result<Self, Error> {
-        let api_base = url::Url::parse(api_base)?;
-        if api_base.cannot_be_a_base() {
-            return Err(Error::InvalidBase(api_base.into()));
-        }
+        Self::builder(api_base).build()
+    }
 
+    /// Create a new client that uses the given `reqwest` client for its requests.
+    ///
+    /// The caller owns the supplied client's configuration (timeouts, TLS,
+    /// proxies, default headers, and so forth); none of the defaults described
+    /// in [`Awair::new`] are applied. Since `reqwest` clients are internally
+    /// reference-counted, this can also be used to share a single connection
+    /// pool between multiple `Awair` instances.
+    pub fn with_client(api_base: &str, client: reqwest::blocking::Client) -> Result<Self, Error> {
         Ok(Self {
-            api_base,
-            http: reqwest::blocking::Client::new(),
+            api_base: parse_base(api_base)?,
+            http: client,
+            retries: 0,
+            backoff: DEFAULT_BACKOFF,
+            min_interval: None,
+            last_poll: Default::default(),
+            cache_ttl: None,
+            cache: Default::default(),
+            breaker: None,
+            fallbacks: Arc::new([]),
+            preferred: Default::default(),
+            hard_timeout: None,
+            firmware: Default::default(),
+            min_firmware: default_min_firmware().into(),
+            latest_path: LATEST_PATH.into(),
+            config_path: CONFIG_PATH.into(),
+            device_id: Default::default(),
+            #[cfg(feature = "chrono-tz")]
+            timezone: Default::default(),
         })
     }
 
+    /// Create a new client for the Awair at the given IP address, on the
+    /// standard HTTP port.
+    pub fn from_addr(addr: IpAddr) -> Result<Self, Error> {
+        Self::from_host_port(&addr.to_string(), 80)
+    }
+
+    /// Create a new client for the Awair at the given host (a domain name or
+    /// IP address) and port.
+    pub fn from_host_port(host: &str, port: u16) -> Result<Self, Error> {
+        Self::new(&host_port_base(host, port))
+    }
+
+    /// Create a new client from a loosely formatted address, e.g. one supplied
+    /// on the command line.
+    ///
+    /// The address can be a full Local API base URL (like
+    /// `http://192.168.1.10`), or a bare IP address or host (like
+    /// `192.168.1.10`, `::1`, or `awair.local:8080`), in which case `http://`
+    /// is assumed.
+    pub fn connect(input: &str) -> Result<Self, Error> {
+        Self::new(&loose_base(input))
+    }
+
+    /// Create a new client for the Awair reachable on any of the given Local
+    /// API bases, e.g. by both its hostname and its IP address.
+    ///
+    /// The first base is the primary one, and the rest are fallbacks; see
+    /// [`AwairBuilder::fallbacks`]. An empty list is an [`Error::NoBases`].
+    pub fn with_fallbacks(bases: &[&str]) -> Result<Self, Error> {
+        let (primary, fallbacks) = bases.split_first().ok_or(Error::NoBases)?;
+        Self::builder(primary).fallbacks(fallbacks).build()
+    }
+
+    /// Create a new client configured from the environment.
+    ///
+    /// See [`AwairBuilder::from_env`] for the variables used.
+    pub fn from_env() -> Result<Self, Error> {
+        AwairBuilder::from_env()?.build()
+    }
+
+    /// Create an [`AwairBuilder`] for a client talking to the given Local API base.
+    pub fn builder(api_base: &str) -> AwairBuilder {
+        AwairBuilder::new(api_base)
+    }
+
+    /// Returns the Local API base URL that this client talks to.
+    pub fn api_base(&self) -> &url::Url {
+        &self.api_base
+    }
+
+    /// Returns the host (a domain name or IP address) that this client talks to.
+    pub fn host(&self) -> Option<&str> {
+        self.api_base.host_str()
+    }
+
     /// Poll the Awair for its latest air quality data.
+    ///
+    /// If the client has a [`cache_ttl`](AwairBuilder::cache_ttl), a cached
+    /// sample younger than the TTL is returned without making a request.
+    /// Otherwise, if the client has a [`min_interval`](AwairBuilder::min_interval),
+    /// this blocks until that interval has elapsed since the last successful poll.
     pub fn poll(&self) -> Result<AirData, Error> {
-        let latest = self.api_base.join("/air-data/latest")?;
+        let Some(cache_ttl) = self.cache_ttl else {
+            return self.poll_limited();
+        };
+
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((fetched, data)) = &*cache {
+            if fetched.elapsed() < cache_ttl {
+                return Ok(data.clone());
+            }
+        }
+
+        let data = self.poll_limited()?;
+        *cache = Some((Instant::now(), data.clone()));
+        Ok(data)
+    }
+
+    /// Discard the cached sample, if any, so that the next [`Awair::poll`]
+    /// makes a request.
+    pub fn invalidate_cache(&self) {
+        *self.cache.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Poll the Awair for its latest air quality data, labeled with the
+    /// device's ID.
+    ///
+    /// The device ID is read from the device's configuration on first use,
+    /// and cached afterwards, so that labeling doesn't cost a request per poll.
+    pub fn poll_labeled(&self) -> Result<LabeledAirData, Error> {
+        let device_id = match cached_device_id(&self.device_id) {
+            Some(device_id) => device_id,
+            None => {
+                let device_id = self.config()?.device_id;
+                cache_device_id(&self.device_id, &device_id);
+                device_id
+            }
+        };
+
+        let data = self.poll()?;
+        Ok(LabeledAirData { device_id, data })
+    }
+
+    /// Poll the Awair for its latest air quality data, returning `None` if
+    /// the sample's timestamp is the same as `last`.
+    ///
+    /// The device only updates its latest sample periodically, so this can be
+    /// used to skip samples that have already been seen.
+    pub fn poll_if_new(&self, last: Option<&DateTime<Utc>>) -> Result<Option<AirData>, Error> {
+        let data = self.poll()?;
+        Ok((last != Some(&data.timestamp)).then_some(data))
+    }
+
+    /// Like [`Awair::poll`], but also return the device's clock skew relative
+    /// to the system time (see [`AirData::clock_skew`]).
+    pub fn poll_with_skew(&self) -> Result<(AirData, chrono::Duration), Error> {
+        let data = self.poll()?;
+        let skew = data.clock_skew(Utc::now());
+        Ok((data, skew))
+    }
+
+    /// Like [`Awair::poll`], but also return how long the request took.
+    ///
+    /// The duration covers the entire round trip, including any retries.
+    pub fn poll_timed(&self) -> Result<(AirData, Duration), Error> {
+        let start = Instant::now();
+        self.poll().map(|data| (data, start.elapsed()))
+    }
+
+    /// Poll the Awair for its latest raw (uncalibrated) air quality data.
+    ///
+    /// Raw samples are taken at the device's fastest sampling rate, and
+    /// share the same schema as [`Awair::poll`].
+    pub fn poll_raw(&self) -> Result<AirData, Error> {
+        self.get(RAW_PATH)
+    }
+
+    /// Poll the Awair for its air quality data, averaged over the last 5 minutes.
+    pub fn five_minute_average(&self) -> Result<AirData, Error> {
+        self.get(FIVE_MIN_AVG_PATH)
+    }
+
+    /// Poll the Awair for its air quality data, averaged over the last 15 minutes.
+    pub fn fifteen_minute_average(&self) -> Result<AirData, Error> {
+        self.get(FIFTEEN_MIN_AVG_PATH)
+    }
+
+    /// Estimate how often the Awair updates its latest sample.
+    ///
+    /// The device's configuration doesn't report its sampling interval, so
+    /// this polls the device every second until its latest sample changes,
+    /// and returns the difference between the two samples' timestamps. This
+    /// bypasses the client's sample cache, and fails with [`Error::NoUpdate`]
+    /// if the sample doesn't change within a minute.
+    pub fn estimate_update_interval(&self) -> Result<Duration, Error> {
+        let start = Instant::now();
+        let first = self.poll_limited()?;
 
-        Ok(self
-            .http
-            .get(latest)
-            .send()?
-            .error_for_status()?
-            .json::<AirData>()?)
+        while start.elapsed() < ESTIMATE_TIMEOUT {
+            thread::sleep(ESTIMATE_POLL_GAP);
+            let next = self.poll_limited()?;
+            if let Some(interval) = update_interval(&first, &next) {
+                return Ok(interval);
+            }
+        }
+
+        Err(Error::NoUpdate(ESTIMATE_TIMEOUT))
+    }
+
+    /// Poll the Awair for its latest air quality data on a fixed cadence.
+    ///
+    /// The returned iterator never terminates on its own: request errors are
+    /// yielded as `Err` items, leaving the caller to decide whether to continue.
+    /// The first sample is taken immediately, and each subsequent sample is
+    /// scheduled relative to the previous one, so the time spent on each
+    /// request doesn't cause the samples to drift.
+    ///
+    /// Intervals shorter than [`MIN_STREAM_INTERVAL`] (including zero) are
+    /// raised to it, so that the device isn't polled in a tight loop.
+    pub fn stream(&self, interval: Duration) -> impl Iterator<Item = Result<AirData, Error>> + '_ {
+        let interval = interval.max(MIN_STREAM_INTERVAL);
+        let mut next = Instant::now();
+
+        std::iter::from_fn(move || {
+            let now = Instant::now();
+            if next > now {
+                thread::sleep(next - now);
+            }
+
+            // If a request overran the interval, reschedule from the present
+            // instead of firing off a burst of requests to catch up.
+            next = (next + interval).max(Instant::now());
+
+            Some(self.poll())
+        })
+    }
+
+    /// Poll the Awair for its latest air quality data on a fixed cadence,
+    /// passing each result to `on_sample` until it returns [`ControlFlow::Break`].
+    ///
+    /// This has the same scheduling as [`Awair::stream`]. Request errors are
+    /// passed to `on_sample` rather than ending the loop.
+    pub fn poll_loop<F>(&self, interval: Duration, mut on_sample: F)
+    where
+        F: FnMut(Result<AirData, Error>) -> ControlFlow<()>,
+    {
+        for sample in self.stream(interval) {
+            if on_sample(sample).is_break() {
+                break;
+            }
+        }
     }
 
     /// Request the Awair's configuration state.
+    ///
+    /// The device's firmware version is remembered, so that later requests
+    /// to endpoints that it's known not to serve fail with
+    /// [`Error::UnsupportedByFirmware`] instead of contacting the device.
     pub fn config(&self) -> Result<DeviceConfig, Error> {
-        let config = self.api_base.join("/settings/config/data")?;
-
-        Ok(self
-            .http
-            .get(config)
-            .send()?
-            .error_for_status()?
-            .json::<DeviceConfig>()?)
+        let config = self.get(&self.config_path)?;
+        remember_firmware(&config, &self.firmware);
+        Ok(config)
+    }
+
+    /// Like [`Awair::config`], but also return how long the request took.
+    pub fn config_timed(&self) -> Result<(DeviceConfig, Duration), Error> {
+        let start = Instant::now();
+        self.config().map(|config| (config, start.elapsed()))
+    }
+
+    /// Change the Awair's display mode, returning the device's updated configuration.
+    ///
+    /// NOTE: The device may silently ignore modes that it doesn't support, so
+    /// the returned configuration's [`DeviceConfig::display`] may not be `mode`.
+    pub fn set_display_mode(&self, mode: DisplayMode) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &DisplayUpdate { display: mode })
+    }
+
+    /// Check whether the Awair is currently using the given display mode, by
+    /// re-reading its configuration.
+    ///
+    /// This is useful for confirming that a change made with
+    /// [`Awair::set_display_mode`] took effect.
+    pub fn verify_display_mode(&self, expected: DisplayMode) -> Result<bool, Error> {
+        Ok(self.config()?.display == expected)
+    }
+
+    /// Change the Awair's LED mode and brightness, returning the device's
+    /// updated configuration.
+    ///
+    /// A `brightness` outside of the range accepted by the device (see
+    /// [`LedConfig::MAX_BRIGHTNESS`]) is an [`Error::InvalidBrightness`], and
+    /// the device isn't contacted.
+    pub fn set_led(&self, mode: LedMode, brightness: u8) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &LedUpdate::new(mode, brightness)?)
+    }
+
+    /// Enable or disable the Awair's knock-to-wake display feature, returning
+    /// the device's updated configuration.
+    pub fn set_knocking(&self, enabled: bool) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &KnockingUpdate { knocking: enabled })
+    }
+
+    /// Apply the writable settings in the given configuration to the Awair,
+    /// returning the device's updated configuration.
+    ///
+    /// This is useful for pushing back an edited [`DeviceConfig`]; see
+    /// [`DeviceConfig::to_update_body`] for which settings are applied, and how
+    /// they're validated before the device is contacted.
+    pub fn apply_config(&self, config: &DeviceConfig) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &config.to_update_body()?)
+    }
+
+    /// Capture the Awair's configuration, e.g. to migrate its settings to
+    /// another device with [`Awair::import_config`].
+    ///
+    /// This is equivalent to [`Awair::config`].
+    pub fn export_config(&self) -> Result<DeviceConfig, Error> {
+        self.config()
+    }
+
+    /// Apply a configuration captured with [`Awair::export_config`] (possibly
+    /// from another device), returning the device's updated configuration.
+    ///
+    /// Only the writable settings are applied: the network and identity
+    /// fields are ignored. This is equivalent to [`Awair::apply_config`].
+    pub fn import_config(&self, config: &DeviceConfig) -> Result<DeviceConfig, Error> {
+        self.apply_config(config)
+    }
+
+    /// Poll the Awair for its latest air quality data, tolerating malformed fields.
+    ///
+    /// Any fields that are malformed or missing are filled in with zeros
+    /// (or `None`, or the Unix epoch for the timestamp), and reported in the
+    /// returned [`FieldError`]s. Request errors are still returned as `Err`.
+    pub fn poll_lenient(&self) -> Result<(AirData, Vec<FieldError>), Error> {
+        Ok(partial::parse(self.poll_raw_json()?))
+    }
+
+    /// Poll the Awair for its latest air quality data, as untyped JSON.
+    ///
+    /// This is the untyped counterpart to [`Awair::poll`](crate::Awair::poll)
+    /// (not [`Awair::poll_raw`](crate::Awair::poll_raw)), and is useful for
+    /// inspecting fields that [`AirData`] doesn't model, or for debugging
+    /// deserialization failures.
+    pub fn poll_raw_json(&self) -> Result<serde_json::Value, Error> {
+        self.get(&self.latest_path)
+    }
+
+    /// Request the Awair's configuration state, as untyped JSON.
+    ///
+    /// This is the untyped counterpart to [`Awair::config`](crate::Awair::config).
+    pub fn config_json(&self) -> Result<serde_json::Value, Error> {
+        self.get(&self.config_path)
+    }
+
+    /// Check whether the Awair is reachable and responding successfully,
+    /// without parsing its response.
+    ///
+    /// Returns `Ok(false)` if the device responds with an unsuccessful HTTP
+    /// status, and an error if it can't be reached at all.
+    pub fn ping(&self) -> Result<bool, Error> {
+        match self.checked(&self.latest_path, |url| self.http.get(url)) {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.without_endpoint(), Error::HttpStatus { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Probe the Awair for the optional endpoints that it supports.
+    ///
+    /// Each endpoint is probed with a request; an endpoint that responds with
+    /// HTTP 404 is considered unsupported, while any other error is returned.
+    pub fn capabilities(&self) -> Result<Capabilities, Error> {
+        Ok(Capabilities {
+            raw: self.probe(RAW_PATH)?,
+            five_min_avg: self.probe(FIVE_MIN_AVG_PATH)?,
+            fifteen_min_avg: self.probe(FIFTEEN_MIN_AVG_PATH)?,
+            settings: self.probe(&self.config_path)?,
+        })
+    }
+
+    /// Poll the Awair for its latest air quality data, subject to the
+    /// client's minimum interval.
+    fn poll_limited(&self) -> Result<AirData, Error> {
+        let Some(min_interval) = self.min_interval else {
+            return self.get(&self.latest_path);
+        };
+
+        // NOTE: The lock is held for the whole request, so that concurrent
+        // pollers are spaced out too.
+        let mut last_poll = self
+            .last_poll
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(elapsed) = last_poll.map(|last_poll| last_poll.elapsed()) {
+            if elapsed < min_interval {
+                thread::sleep(min_interval - elapsed);
+            }
+        }
+
+        let data = self.get(&self.latest_path)?;
+        *last_poll = Some(Instant::now());
+        Ok(data)
+    }
+
+    /// Issue a GET request to the given endpoint, deserializing its JSON response.
+    ///
+    /// GET requests are idempotent, so transient failures are retried according
+    /// to the client's retry policy.
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        check_firmware(path, &self.firmware, &self.min_firmware)?;
+        self.with_retries(|| self.send(path, |url| self.http.get(url)))
+    }
+
+    /// Run the given operation, retrying it with exponential backoff for as
+    /// long as it fails transiently and the retry policy allows.
+    fn with_retries<T>(&self, mut op: impl FnMut() -> Result<T, Error>) -> Result<T, Error> {
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Err(e) if e.is_transient() && attempt < self.retries => {
+                    thread::sleep(backoff_delay(self.backoff, attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Issue a PUT request with the given JSON body to the given endpoint,
+    /// deserializing its JSON response.
+    fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T, Error> {
+        self.send(path, |url| self.http.put(url).json(body))
+    }
+
+    /// Check whether the given endpoint exists, by issuing a GET request to it.
+    fn probe(&self, path: &str) -> Result<bool, Error> {
+        match self.checked(path, |url| self.http.get(url)) {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.without_endpoint(), Error::HttpStatus { status: 404, .. }) => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Send the request built by `build` to the given endpoint, deserializing
+    /// its JSON response.
+    fn send<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        build: impl Fn(url::Url) -> RequestBuilder,
+    ) -> Result<T, Error> {
+        let response = self.checked(path, build)?;
+        check_content_type(response.headers()).map_err(|e| e.at(path))?;
+        response.json::<T>().map_err(|e| Error::from(e).at(path))
+    }
+
+    /// Returns the base with the given index, where the primary base is 0 and
+    /// the fallbacks follow in order.
+    fn base(&self, index: usize) -> &url::Url {
+        index
+            .checked_sub(1)
+            .and_then(|index| self.fallbacks.get(index))
+            .unwrap_or(&self.api_base)
+    }
+
+    /// Send the request built by `build` to the given endpoint, turning an
+    /// unsuccessful response into an error.
+    ///
+    /// The last base that the device responded on is tried first. If that
+    /// fails transiently, the remaining bases are tried in order.
+    fn checked(
+        &self,
+        path: &str,
+        build: impl Fn(url::Url) -> RequestBuilder,
+    ) -> Result<Response, Error> {
+        let count = self.fallbacks.len() + 1;
+        let preferred = self.preferred.load(atomic::Ordering::Relaxed) % count;
+
+        let mut index = preferred;
+        let mut result = self.attempt(path, build(endpoint_url(self.base(index), path)?));
+        for offset in 1..count {
+            if !matches!(&result, Err(e) if e.is_transient()) {
+                break;
+            }
+
+            index = (preferred + offset) % count;
+            result = self.attempt(path, build(endpoint_url(self.base(index), path)?));
+        }
+
+        if !matches!(&result, Err(e) if e.is_transient()) {
+            self.preferred.store(index, atomic::Ordering::Relaxed);
+        }
+
+        result.map_err(|e| e.at(path))
+    }
+
+    /// Send the given request to the given endpoint, turning an unsuccessful
+    /// response into an error.
+    #[cfg_attr(
+        not(any(feature = "log", feature = "tracing")),
+        allow(unused_variables)
+    )]
+    fn attempt(&self, path: &str, request: RequestBuilder) -> Result<Response, Error> {
+        if let Some(breaker) = &self.breaker {
+            breaker.check()?;
+        }
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "awair_request",
+            path,
+            status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        )
+        .entered();
+        #[cfg(feature = "tracing")]
+        let start = Instant::now();
+
+        let response = request.build().map_err(Error::from).and_then(|request| {
+            #[cfg(feature = "log")]
+            log::debug!("{} {}", request.method(), request.url());
+
+            self.execute(request)
+        });
+
+        let result = match response {
+            Ok(response) => {
+                #[cfg(feature = "tracing")]
+                {
+                    span.record("status", response.status().as_u16());
+                    span.record("latency_ms", start.elapsed().as_millis() as u64);
+                }
+                #[cfg(feature = "log")]
+                log_response_size(path, response.content_length());
+
+                check_status(response)
+            }
+            Err(e) => Err(e),
+        };
+
+        #[cfg(feature = "tracing")]
+        if let Err(e) = &result {
+            tracing::debug!(error = %e, "request failed");
+        }
+        #[cfg(feature = "log")]
+        if let Err(e) = &result {
+            log::warn!("request to {path} failed: {e}");
+        }
+
+        if let Some(breaker) = &self.breaker {
+            breaker.record(&result);
+        }
+
+        result
+    }
+
+    /// Execute the given request, giving up on it once the hard timeout (if
+    /// any) has elapsed.
+    fn execute(&self, request: reqwest::blocking::Request) -> Result<Response, Error> {
+        let Some(hard_timeout) = self.hard_timeout else {
+            return Ok(self.http.execute(request)?);
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let http = self.http.clone();
+        // NOTE: If the deadline passes, the thread is detached and left to
+        // finish (or hang) on its own; its result is discarded.
+        thread::spawn(move || {
+            let _ = sender.send(http.execute(request));
+        });
+
+        match receiver.recv_timeout(hard_timeout) {
+            Ok(response) => Ok(response?),
+            // NOTE: The thread only hangs up without sending if it panicked,
+            // in which case no response is coming either.
+            Err(_) => Err(Error::Timeout(None)),
+        }
+    }
+}
+
+/// Turn an unsuccessful response into an [`Error::HttpStatus`].
+fn check_status(response: Response) -> Result<Response, Error> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(status_error(status, response.text().unwrap_or_default()));
+    }
+
+    Ok(response)
+}
+
+/// Returns the [`Error::HttpStatus`] for an unsuccessful response with the
+/// given status and body. Shared by the blocking and asynchronous clients.
+fn status_error(status: reqwest::StatusCode, body: String) -> Error {
+    Error::HttpStatus {
+        status: status.as_u16(),
+        body,
+    }
+}
+
+/// Log the size of a response's body (its `Content-Length`, if any), as
+/// declared by the device. Shared by the blocking and asynchronous clients.
+#[cfg(feature = "log")]
+fn log_response_size(path: &str, content_length: Option<u64>) {
+    match content_length {
+        Some(size) => log::trace!("{path}: {size} byte response"),
+        None => log::trace!("{path}: response of unknown size"),
+    }
+}
+
+/// Reject a response that declares a content type other than JSON.
+///
+/// Responses that don't declare a content type are assumed to be JSON.
+fn check_content_type(headers: &HeaderMap) -> Result<(), Error> {
+    let Some(content_type) = headers.get(CONTENT_TYPE) else {
+        return Ok(());
+    };
+
+    let content_type = String::from_utf8_lossy(content_type.as_bytes());
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    if essence.eq_ignore_ascii_case("application/json") || essence.ends_with("+json") {
+        return Ok(());
+    }
+
+    Err(Error::UnexpectedContentType {
+        content_type: content_type.into(),
+    })
+}
+
+/// The environment variable holding a Local API base URL.
+const BASE_URL_VAR: &str = "AWAIR_BASE_URL";
+
+/// The environment variable holding a device's host.
+const HOST_VAR: &str = "AWAIR_HOST";
+
+/// The environment variable holding a device's port.
+const PORT_VAR: &str = "AWAIR_PORT";
+
+/// Read an environment variable required to configure a client.
+fn env_var(var: &'static str) -> Result<String, Error> {
+    env::var(var).map_err(|source| Error::Env { var, source })
+}
+
+impl TryFrom<&str> for Awair {
+    type Error = Error;
+
+    /// Equivalent to [`Awair::new`].
+    fn try_from(api_base: &str) -> Result<Self, Error> {
+        Self::new(api_base)
+    }
+}
+
+impl TryFrom<url::Url> for Awair {
+    type Error = Error;
+
+    /// Equivalent to [`Awair::new`]: the URL must still be a usable HTTP or
+    /// HTTPS base.
+    fn try_from(api_base: url::Url) -> Result<Self, Error> {
+        Self::new(api_base.as_str())
+    }
+}
+
+/// A builder for configuring an [`Awair`] client.
+#[derive(Debug)]
+pub struct AwairBuilder {
+    api_base: String,
+    timeout: Duration,
+    connect_timeout: Option<Duration>,
+    retries: u32,
+    backoff: Duration,
+    min_interval: Option<Duration>,
+    cache_ttl: Option<Duration>,
+    circuit_breaker: Option<(u32, Duration)>,
+    fallbacks: Vec<String>,
+    hard_timeout: Option<Duration>,
+    client: Option<reqwest::blocking::Client>,
+    latest_path: String,
+    config_path: String,
+    min_firmware: Vec<(String, semver::Version)>,
+    user_agent: String,
+    proxy: Option<reqwest::Proxy>,
+    no_proxy: bool,
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    accept_invalid_certs: bool,
+}
+
+impl AwairBuilder {
+    /// Create a new builder for a client talking to the given Local API base.
+    pub fn new(api_base: &str) -> Self {
+        Self {
+            api_base: api_base.into(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: None,
+            retries: 0,
+            backoff: DEFAULT_BACKOFF,
+            min_interval: None,
+            cache_ttl: None,
+            circuit_breaker: None,
+            fallbacks: Vec::new(),
+            hard_timeout: None,
+            client: None,
+            latest_path: LATEST_PATH.into(),
+            config_path: CONFIG_PATH.into(),
+            min_firmware: default_min_firmware(),
+            user_agent: DEFAULT_USER_AGENT.into(),
+            proxy: None,
+            no_proxy: false,
+            #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+            accept_invalid_certs: false,
+        }
+    }
+
+    /// Create a new builder for the Local API base given by the environment.
+    ///
+    /// The base is read from `AWAIR_BASE_URL` if set, or is otherwise built
+    /// from `AWAIR_HOST` and `AWAIR_PORT` (which defaults to 80). If neither
+    /// `AWAIR_BASE_URL` nor `AWAIR_HOST` is set, an [`Error::Env`] for
+    /// `AWAIR_BASE_URL` is returned.
+    pub fn from_env() -> Result<Self, Error> {
+        if env::var_os(BASE_URL_VAR).is_some() || env::var_os(HOST_VAR).is_none() {
+            return Ok(Self::new(&env_var(BASE_URL_VAR)?));
+        }
+
+        let host = env_var(HOST_VAR)?;
+        let port = match env::var(PORT_VAR) {
+            Ok(port) => port.parse().map_err(|_| Error::InvalidEnv {
+                var: PORT_VAR,
+                value: port,
+            })?,
+            Err(env::VarError::NotPresent) => 80,
+            Err(source) => {
+                return Err(Error::Env {
+                    var: PORT_VAR,
+                    source,
+                })
+            }
+        };
+
+        Ok(Self::new(&host_port_base(&host, port)))
+    }
+
+    /// Set the total timeout for each request, from connecting until the
+    /// response body has been read.
+    ///
+    /// Defaults to [`DEFAULT_TIMEOUT`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for the connection phase of each request.
+    ///
+    /// By default, only the total [`timeout`](AwairBuilder::timeout) applies.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Set the number of times to retry a request that fails transiently
+    /// (see [`Error::is_transient`]).
+    ///
+    /// Only requests that read from the device are retried. Defaults to 0,
+    /// i.e. no retries.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set the base delay between retries.
+    ///
+    /// The delay doubles with each subsequent retry, and is subject to a small
+    /// amount of random jitter. Defaults to [`DEFAULT_BACKOFF`].
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the minimum interval between polls for the latest air quality data.
+    ///
+    /// When set, [`Awair::poll`] (and everything built on it) blocks until
+    /// the interval has elapsed since the last successful poll, protecting
+    /// the device from being polled faster than it can keep up with. By
+    /// default, polls aren't limited.
+    pub fn min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = Some(min_interval);
+        self
+    }
+
+    /// Cache the latest air quality data for the given time-to-live.
+    ///
+    /// When set, [`Awair::poll`] returns the cached sample until it's older
+    /// than the TTL, or until [`Awair::invalidate_cache`] is called. By
+    /// default, samples aren't cached.
+    pub fn cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = Some(cache_ttl);
+        self
+    }
+
+    /// Stop contacting the device for `cooldown` after `failures` consecutive
+    /// transient failures (see [`Error::is_transient`]).
+    ///
+    /// While the breaker is open, every request fails immediately with
+    /// [`Error::CircuitOpen`]. Once the cooldown has elapsed, requests are
+    /// allowed through again: a success closes the breaker, while another
+    /// transient failure reopens it. A `failures` of zero is treated as one.
+    /// By default, there's no circuit breaker.
+    pub fn circuit_breaker(mut self, failures: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some((failures, cooldown));
+        self
+    }
+
+    /// Fall back to the given Local API bases, in order, when the device can't
+    /// be reached on the primary base.
+    ///
+    /// Only transient failures (see [`Error::is_transient`]) trigger a
+    /// failover. The client remembers the last base that the device
+    /// responded on, and tries it first for subsequent requests.
+    pub fn fallbacks(mut self, fallbacks: &[&str]) -> Self {
+        self.fallbacks = fallbacks.iter().map(|&base| base.into()).collect();
+        self
+    }
+
+    /// Give up on each request after the given duration, regardless of the
+    /// underlying `reqwest` client's own timeouts.
+    ///
+    /// Each request is run on a separate thread, and is abandoned with an
+    /// [`Error::Timeout`] if it doesn't complete in time. Abandoned requests
+    /// aren't cancelled: the device may still receive and act on them, e.g.
+    /// by applying a settings change.
+    ///
+    /// This is mostly useful with a [custom client](AwairBuilder::client)
+    /// that has no timeout of its own. By default, no hard timeout applies.
+    pub fn hard_timeout(mut self, hard_timeout: Duration) -> Self {
+        self.hard_timeout = Some(hard_timeout);
+        self
+    }
+
+    /// Request the latest air quality sample from the given endpoint, instead
+    /// of `/air-data/latest`.
+    ///
+    /// This is an escape hatch for firmware that renames the endpoint.
+    pub fn latest_path(mut self, latest_path: &str) -> Self {
+        self.latest_path = latest_path.into();
+        self
+    }
+
+    /// Request and change the device's configuration at the given endpoint,
+    /// instead of `/settings/config/data`.
+    ///
+    /// This is an escape hatch for firmware that renames the endpoint.
+    pub fn config_path(mut self, config_path: &str) -> Self {
+        self.config_path = config_path.into();
+        self
+    }
+
+    /// Require at least the given firmware version for requests to the given
+    /// endpoint path (e.g. `/air-data/5-min-avg`), replacing any existing
+    /// minimum for it.
+    ///
+    /// Once the device's firmware version is known (from [`Awair::config`]),
+    /// requests to the endpoint on older firmware fail with
+    /// [`Error::UnsupportedByFirmware`] instead of contacting the device.
+    pub fn min_firmware(mut self, endpoint: &str, required: semver::Version) -> Self {
+        self.min_firmware
+            .retain(|(existing, _)| existing != endpoint);
+        self.min_firmware.push((endpoint.into(), required));
+        self
+    }
+
+    /// Use the given `reqwest` client for requests, instead of building one.
+    ///
+    /// As with [`Awair::with_client`], the caller owns the supplied client's
+    /// configuration: the [`timeout`](AwairBuilder::timeout),
+    /// [`connect_timeout`](AwairBuilder::connect_timeout),
+    /// [`user_agent`](AwairBuilder::user_agent), and proxy and TLS options
+    /// are ignored.
+    pub fn client(mut self, client: reqwest::blocking::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send the given `User-Agent` header with every request.
+    ///
+    /// Defaults to [`DEFAULT_USER_AGENT`].
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Send requests through the given proxy.
+    ///
+    /// Hosts that should bypass the proxy can be excluded with
+    /// [`reqwest::Proxy::no_proxy`]. By default, proxies are detected from
+    /// the environment (e.g. `HTTP_PROXY`).
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Connect to the device directly, ignoring any proxies configured in the
+    /// environment.
+    ///
+    /// This takes precedence over [`proxy`](AwairBuilder::proxy).
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Accept invalid TLS certificates when talking to an `https://` base.
+    ///
+    /// This is intended for Awairs fronted by a reverse proxy with a
+    /// self-signed certificate. Defaults to `false`.
+    ///
+    /// # Security
+    ///
+    /// Enabling this disables **all** certificate verification, including
+    /// expiry and hostname checks. Anyone able to intercept traffic between
+    /// this client and the proxy can impersonate the device and read or
+    /// modify its data and settings. Only enable this on a network you trust.
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    pub fn danger_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Build the configured [`Awair`] client.
+    pub fn build(self) -> Result<Awair, Error> {
+        let http = match &self.client {
+            Some(client) => client.clone(),
+            None => self.http_client()?,
+        };
+
+        Ok(Awair {
+            retries: self.retries,
+            backoff: self.backoff,
+            min_interval: self.min_interval,
+            cache_ttl: self.cache_ttl,
+            fallbacks: self
+                .fallbacks
+                .iter()
+                .map(|base| parse_base(base))
+                .collect::<Result<_, _>>()?,
+            breaker: self
+                .circuit_breaker
+                .map(|(failures, cooldown)| Arc::new(CircuitBreaker::new(failures, cooldown))),
+            hard_timeout: self.hard_timeout,
+            latest_path: self.latest_path,
+            config_path: self.config_path,
+            min_firmware: self.min_firmware.into(),
+            ..Awair::with_client(&self.api_base, http)?
+        })
+    }
+
+    /// Build the `reqwest` client configured by this builder.
+    fn http_client(&self) -> Result<reqwest::blocking::Client, Error> {
+        let mut http = reqwest::blocking::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent);
+        if let Some(connect_timeout) = self.connect_timeout {
+            http = http.connect_timeout(connect_timeout);
+        }
+        if self.no_proxy {
+            http = http.no_proxy();
+        } else if let Some(proxy) = &self.proxy {
+            http = http.proxy(proxy.clone());
+        }
+        #[cfg(feature = "rustls-tls")]
+        {
+            http = http.use_rustls_tls();
+        }
+        #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+        {
+            http = http.danger_accept_invalid_certs(self.accept_invalid_certs);
+        }
+
+        Ok(http.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::testing::{MockAwair, MockResponse, SAMPLE_AIR_DATA, SAMPLE_CONFIG};
+
+    /// Returns the sample served by a fresh mock.
+    fn sample() -> AirData {
+        serde_json::from_str(SAMPLE_AIR_DATA).unwrap()
+    }
+
+    /// Returns the configuration served by a fresh mock.
+    fn config() -> DeviceConfig {
+        serde_json::from_str(SAMPLE_CONFIG).unwrap()
+    }
+
+    #[test]
+    fn test_stream_spacing() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        let start = Instant::now();
+        let samples = client
+            .stream(Duration::from_millis(200))
+            .take(3)
+            .collect::<Vec<_>>();
+        let elapsed = start.elapsed();
+
+        assert_eq!(samples.len(), 3);
+        assert!(samples.iter().all(Result::is_ok));
+        assert!(elapsed >= Duration::from_millis(400));
+        assert!(elapsed < Duration::from_millis(2000));
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_stream_zero_interval() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        let start = Instant::now();
+        assert_eq!(client.stream(Duration::ZERO).take(3).count(), 3);
+        assert!(start.elapsed() >= MIN_STREAM_INTERVAL * 2);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_delay(Duration::from_secs(3));
+        let client = Awair::builder(&mock.api_base())
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+
+        let start = Instant::now();
+        let err = client.poll().unwrap_err();
+        let elapsed = start.elapsed();
+        assert!(matches!(err.without_endpoint(), Error::Timeout(Some(_))));
+        assert!(elapsed >= Duration::from_millis(500));
+        assert!(elapsed < Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_poll_raw() {
+        let mock = MockAwair::start().unwrap();
+        let raw = serde_json::json!({
+            "timestamp": "2024-01-01T00:00:10.000Z",
+            "score": 88,
+            "dew_point": 8.41,
+            "temp": 21.37,
+            "humid": 42.18,
+            "abs_humid": 7.88,
+            "co2": 615,
+            "co2_est": 452,
+            "co2_est_baseline": 35012,
+            "voc": 214,
+            "voc_baseline": 37034,
+            "voc_h2_raw": 26,
+            "voc_ethanol_raw": 38,
+            "pm25": 3,
+            "pm10_est": 4
+        });
+        mock.set_response(RAW_PATH, MockResponse::json(&raw));
+
+        let data = mock.awair().unwrap().poll_raw().unwrap();
+        assert_eq!(data.score, 88);
+        assert_eq!(data.temperature, 21.37);
+        assert_eq!(data.estimated_co2_baseline, Some(35012));
+        assert_eq!(mock.requests()[0].path, RAW_PATH);
+    }
+
+    #[test]
+    fn test_averaged_endpoints() {
+        let mock = MockAwair::start().unwrap();
+        for (path, score) in [(FIVE_MIN_AVG_PATH, 80), (FIFTEEN_MIN_AVG_PATH, 70)] {
+            let mut data = serde_json::to_value(mock.awair().unwrap().poll().unwrap()).unwrap();
+            data["score"] = score.into();
+            mock.set_response(path, MockResponse::json(&data));
+        }
+
+        let client = mock.awair().unwrap();
+        assert_eq!(client.five_minute_average().unwrap().score, 80);
+        assert_eq!(client.fifteen_minute_average().unwrap().score, 70);
+
+        let paths = mock
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            &paths[2..],
+            [FIVE_MIN_AVG_PATH, FIFTEEN_MIN_AVG_PATH].as_slice()
+        );
+    }
+
+    /// Returns the JSON body of the given request.
+    fn json_body(request: &crate::testing::MockRequest) -> serde_json::Value {
+        serde_json::from_slice(&request.body).unwrap()
+    }
+
+    #[test]
+    fn test_set_display_mode() {
+        let mock = MockAwair::start().unwrap();
+        let config = mock
+            .awair()
+            .unwrap()
+            .set_display_mode(DisplayMode::Temperature)
+            .unwrap();
+        assert_eq!(config.display, DisplayMode::Temperature);
+        assert_eq!(config.device_id, "awair-element_1234");
+
+        let request = &mock.requests()[0];
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.path, CONFIG_PATH);
+        assert_eq!(json_body(request), serde_json::json!({ "display": "temp" }));
+    }
+
+    #[test]
+    fn test_set_led() {
+        let mock = MockAwair::start().unwrap();
+        let config = mock.awair().unwrap().set_led(LedMode::Manual, 40).unwrap();
+        assert_eq!(
+            config.led,
+            LedConfig {
+                mode: LedMode::Manual,
+                brightness: 40
+            }
+        );
+
+        assert_eq!(
+            json_body(&mock.requests()[0]),
+            serde_json::json!({ "led": { "mode": "manual", "brightness": 40 } })
+        );
+    }
+
+    #[test]
+    fn test_set_knocking() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        assert_eq!(client.set_knocking(true).unwrap().knocking, Some(true));
+        assert_eq!(client.set_knocking(false).unwrap().knocking, Some(false));
+
+        let requests = mock.requests();
+        assert_eq!(
+            json_body(&requests[0]),
+            serde_json::json!({ "knocking": "on" })
+        );
+        assert_eq!(
+            json_body(&requests[1]),
+            serde_json::json!({ "knocking": "off" })
+        );
+    }
+
+    #[test]
+    fn test_knocking_lenient() {
+        let mut config: serde_json::Value = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+
+        for (knocking, expected) in [
+            (serde_json::json!("on"), Some(true)),
+            (serde_json::json!("off"), Some(false)),
+            (serde_json::json!(true), Some(true)),
+            (serde_json::json!(false), Some(false)),
+            (serde_json::json!(1), Some(true)),
+            (serde_json::json!(0), Some(false)),
+            (serde_json::json!("sideways"), None),
+            (serde_json::json!(2), None),
+            (serde_json::json!(null), None),
+        ] {
+            config["knocking"] = knocking;
+            let config: DeviceConfig = serde_json::from_value(config.clone()).unwrap();
+            assert_eq!(config.knocking, expected);
+        }
+    }
+
+    #[test]
+    fn test_timeout_error() {
+        // NOTE: The listener never accepts, so requests connect but get no response.
+        let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let client = Awair::builder(&format!("http://{}", listener.local_addr().unwrap()))
+            .timeout(Duration::from_millis(300))
+            .build()
+            .unwrap();
+
+        let err = client.poll().unwrap_err();
+        assert!(matches!(err.without_endpoint(), Error::Timeout(Some(_))));
+        assert!(err.is_transient());
+    }
+
+    /// Returns a base URL on which nothing is listening.
+    fn unreachable_base() -> String {
+        // NOTE: Nothing listens on the port once the listener is dropped.
+        let addr = std::net::TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn test_connect_error() {
+        let client = Awair::new(&unreachable_base()).unwrap();
+
+        let err = client.poll().unwrap_err();
+        assert!(matches!(err.without_endpoint(), Error::Connect(_)));
+        assert!(err.is_transient());
+    }
+
+    #[test]
+    fn test_http_status_error() {
+        let mock = MockAwair::start().unwrap();
+        mock.queue_response(MockResponse::new(503, "warming up"));
+
+        let err = mock.awair().unwrap().poll().unwrap_err();
+        match err.without_endpoint() {
+            Error::HttpStatus { status, body } => {
+                assert_eq!(*status, 503);
+                assert_eq!(body, "warming up");
+            }
+            e => panic!("unexpected error: {e:?}"),
+        }
+        assert!(err.to_string().contains("HTTP 503: warming up"));
+    }
+
+    #[test]
+    fn test_temperature_conversions() {
+        let mut data = sample();
+
+        data.temperature = 0.0;
+        data.dew_point = 0.0;
+        assert_eq!(data.temperature_fahrenheit(), 32.0);
+        assert_eq!(data.temperature_kelvin(), 273.15);
+        assert_eq!(data.dew_point_fahrenheit(), 32.0);
+
+        data.temperature = 100.0;
+        data.dew_point = -40.0;
+        assert_eq!(data.temperature_fahrenheit(), 212.0);
+        assert_eq!(data.temperature_kelvin(), 373.15);
+        assert_eq!(data.dew_point_fahrenheit(), -40.0);
+    }
+
+    #[test]
+    fn test_optional_fields_absent() {
+        let data: AirData = serde_json::from_str(
+            r#"{
+                "timestamp": "2024-01-01T00:00:00.000Z",
+                "score": 85,
+                "dew_point": 9.1,
+                "temp": 22.0,
+                "humid": 45.0,
+                "abs_humid": 8.8,
+                "co2": 700,
+                "voc": 300,
+                "voc_baseline": 36000,
+                "voc_h2_raw": 25,
+                "voc_ethanol_raw": 37
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(data.pm25, None);
+        assert_eq!(data.estimated_pm10, None);
+        assert_eq!(data.estimated_co2, None);
+        assert_eq!(data.estimated_co2_baseline, None);
+        assert_eq!(data.co2, 700);
+    }
+
+    #[test]
+    fn test_network_fields() {
+        let mut config = config();
+        assert_eq!(config.ip().unwrap(), Ipv4Addr::new(127, 0, 0, 1));
+        assert_eq!(config.netmask().unwrap(), Ipv4Addr::new(255, 255, 255, 0));
+        assert_eq!(config.gateway().unwrap(), Ipv4Addr::new(127, 0, 0, 1));
+        assert_eq!(
+            config.wifi_mac().unwrap(),
+            MacAddr6::new(0x70, 0x88, 0x6B, 0x00, 0x00, 0x01)
+        );
+
+        config.ip = "192.168.1".into();
+        config.netmask = "".into();
+        config.wifi_mac = "70:88:6B".into();
+        assert!(matches!(
+            config.ip(),
+            Err(Error::InvalidAddress { field: "ip", value }) if value == "192.168.1"
+        ));
+        assert!(matches!(
+            config.netmask(),
+            Err(Error::InvalidAddress {
+                field: "netmask",
+                ..
+            })
+        ));
+        assert!(matches!(
+            config.wifi_mac(),
+            Err(Error::InvalidAddress {
+                field: "wifi_mac",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_firmware_version() {
+        let mut config = config();
+        for (version, expected) in [
+            ("1.2.4", "1.2.4"),
+            ("1.4", "1.4.0"),
+            ("2", "2.0.0"),
+            (" 1.3.1 ", "1.3.1"),
+            ("1.4-beta.1", "1.4.0-beta.1"),
+        ] {
+            config.firmware_version = version.into();
+            assert_eq!(
+                config.firmware_version().unwrap(),
+                semver::Version::parse(expected).unwrap(),
+                "{version:?}"
+            );
+        }
+
+        config.firmware_version = "unknown".into();
+        assert!(matches!(
+            config.firmware_version(),
+            Err(Error::VersionParse(version)) if version == "unknown"
+        ));
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(RAW_PATH, MockResponse::new(404, ""));
+        mock.set_response(FIFTEEN_MIN_AVG_PATH, MockResponse::new(404, ""));
+
+        assert_eq!(
+            mock.awair().unwrap().capabilities().unwrap(),
+            Capabilities {
+                raw: false,
+                five_min_avg: true,
+                fifteen_min_avg: false,
+                settings: true,
+            }
+        );
+
+        mock.set_response(RAW_PATH, MockResponse::new(500, ""));
+        assert!(mock.awair().unwrap().capabilities().is_err());
+    }
+
+    #[test]
+    fn test_retries() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::builder(&mock.api_base())
+            .timeout(Duration::from_millis(200))
+            .retries(2)
+            .backoff(Duration::from_millis(10))
+            .build()
+            .unwrap();
+
+        // NOTE: Each delayed response times out, which is a transient failure.
+        for _ in 0..2 {
+            mock.queue_response(MockResponse::new(200, "").delay(Duration::from_secs(1)));
+        }
+        assert_eq!(client.poll().unwrap().score, 90);
+        assert_eq!(mock.requests().len(), 3);
+
+        for _ in 0..3 {
+            mock.queue_response(MockResponse::new(200, "").delay(Duration::from_secs(1)));
+        }
+        assert!(matches!(
+            client.poll().unwrap_err().without_endpoint(),
+            Error::Timeout(_)
+        ));
+    }
+
+    #[test]
+    fn test_no_retries_for_http_errors() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::builder(&mock.api_base()).retries(2).build().unwrap();
+
+        mock.queue_response(MockResponse::new(500, ""));
+        assert!(client.poll().is_err());
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let data = sample();
+        let max_age = chrono::Duration::minutes(5);
+
+        let fresh = data.timestamp + chrono::Duration::minutes(1);
+        assert_eq!(data.age(fresh), chrono::Duration::minutes(1));
+        assert!(!data.is_stale(fresh, max_age));
+
+        // Exactly `max_age` old isn't stale yet.
+        assert!(!data.is_stale(data.timestamp + max_age, max_age));
+
+        let stale = data.timestamp + chrono::Duration::minutes(6);
+        assert!(data.is_stale(stale, max_age));
+
+        let past = data.timestamp - chrono::Duration::minutes(10);
+        assert!(data.age(past) < chrono::Duration::zero());
+        assert!(!data.is_stale(past, max_age));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut data = sample();
+        assert_eq!(
+            data.to_string(),
+            "Score: 90  Temp: 21.4°C  Humidity: 42%  CO2: 612ppm  VOC: 210ppb  PM2.5: 3µg/m³"
+        );
+
+        data.pm25 = None;
+        assert_eq!(
+            data.to_string(),
+            "Score: 90  Temp: 21.4°C  Humidity: 42%  CO2: 612ppm  VOC: 210ppb"
+        );
+    }
+
+    #[test]
+    fn test_api_base() {
+        let client = Awair::new("http://192.168.1.10").unwrap();
+        assert_eq!(client.api_base().as_str(), "http://192.168.1.10/");
+        assert_eq!(client.host(), Some("192.168.1.10"));
+    }
+
+    #[test]
+    fn test_from_addr() {
+        let client = Awair::from_addr(IpAddr::from([192, 168, 1, 10])).unwrap();
+        assert_eq!(client.api_base().as_str(), "http://192.168.1.10/");
+
+        let client = Awair::from_addr("fe80::1".parse().unwrap()).unwrap();
+        assert_eq!(client.api_base().as_str(), "http://[fe80::1]/");
+        assert_eq!(client.host(), Some("[fe80::1]"));
+    }
+
+    #[test]
+    fn test_from_host_port() {
+        let client = Awair::from_host_port("awair.local", 8080).unwrap();
+        assert_eq!(client.api_base().as_str(), "http://awair.local:8080/");
+
+        let client = Awair::from_host_port("::1", 8080).unwrap();
+        assert_eq!(client.api_base().as_str(), "http://[::1]:8080/");
+    }
+
+    #[test]
+    fn test_unsupported_scheme() {
+        for base in ["mailto:foo", "ftp://host"] {
+            match Awair::new(base) {
+                Err(Error::UnsupportedScheme(url)) => assert_eq!(url, base),
+                other => panic!("unexpected result for {base}: {other:?}"),
+            }
+        }
+
+        assert!(Awair::new("http://host").is_ok());
+        assert!(matches!(Awair::new("not a url"), Err(Error::InvalidUrl(_))));
+    }
+
+    #[test]
+    fn test_endpoint_url() {
+        for (base, expected) in [
+            ("http://host", "http://host/air-data/latest"),
+            ("http://host/", "http://host/air-data/latest"),
+            ("http://host/prefix", "http://host/prefix/air-data/latest"),
+            ("http://host/prefix/", "http://host/prefix/air-data/latest"),
+        ] {
+            let base = parse_base(base).unwrap();
+            assert_eq!(endpoint_url(&base, LATEST_PATH).unwrap().as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_prefixed_base() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&format!("{}/awair/", mock.api_base())).unwrap();
+
+        // The mock doesn't serve anything under the prefix; we only care
+        // about which path was requested.
+        let _ = client.poll();
+        assert_eq!(mock.requests()[0].path, "/awair/air-data/latest");
+    }
+
+    #[test]
+    fn test_raw_json() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let expected: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        assert_eq!(client.poll_raw_json().unwrap(), expected);
+
+        let expected: serde_json::Value = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+        assert_eq!(client.config_json().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        payload["radon"] = serde_json::json!(42);
+        let data: AirData = serde_json::from_value(payload).unwrap();
+        assert_eq!(data.extra.get("radon"), Some(&serde_json::json!(42)));
+        assert_eq!(data.extra.len(), 1);
+
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+        payload["new_setting"] = serde_json::json!("on");
+        let extended: DeviceConfig = serde_json::from_value(payload).unwrap();
+        assert_eq!(
+            extended.extra.get("new_setting"),
+            Some(&serde_json::json!("on"))
+        );
+
+        // Known fields don't end up in the extras.
+        assert!(sample().extra.is_empty());
+        assert!(config().extra.is_empty());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let data = sample();
+        let round_tripped: AirData =
+            serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+        assert_eq!(round_tripped, data);
+
+        let config = config();
+        let round_tripped: DeviceConfig =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_tripped, config);
+
+        let led = config.led;
+        let round_tripped: LedConfig =
+            serde_json::from_str(&serde_json::to_string(&led).unwrap()).unwrap();
+        assert_eq!(round_tripped, led);
+    }
+
+    #[test]
+    fn test_air_data_ordering() {
+        let earlier = sample();
+        let mut later = sample();
+        later.timestamp += chrono::Duration::seconds(10);
+        later.score = 10;
+
+        assert!(earlier < later);
+        assert_eq!(earlier.partial_cmp(&later), Some(std::cmp::Ordering::Less));
+    }
+
+    /// The name and value of each field recorded on a span or event.
+    #[cfg(feature = "tracing")]
+    type Fields = Vec<(String, String)>;
+
+    /// A `tracing` subscriber that records the fields of each span and event.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct CapturingSubscriber {
+        spans: Arc<Mutex<Vec<(&'static str, Fields)>>>,
+        events: Arc<Mutex<Vec<Fields>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.push((field.name().into(), value.into()));
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn fmt::Debug) {
+            self.0.push((field.name().into(), format!("{value:?}")));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for CapturingSubscriber {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Vec::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((attrs.metadata().name(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+            values.record(&mut FieldVisitor(fields));
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = Vec::new();
+            event.record(&mut FieldVisitor(&mut fields));
+            self.events.lock().unwrap().push(fields);
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(CONFIG_PATH, MockResponse::new(500, ""));
+        let client = Awair::new(&mock.api_base()).unwrap();
+        let subscriber = CapturingSubscriber::default();
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            client.poll().unwrap();
+            client.config().unwrap_err();
+        });
+
+        let spans = subscriber.spans.lock().unwrap();
+        let field = |fields: &Fields, name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        let (name, fields) = &spans[0];
+        assert_eq!(*name, "awair_request");
+        assert_eq!(field(fields, "path").as_deref(), Some(LATEST_PATH));
+        assert_eq!(field(fields, "status").as_deref(), Some("200"));
+        assert!(field(fields, "latency_ms").is_some());
+
+        let (_, fields) = &spans[1];
+        assert_eq!(field(fields, "path").as_deref(), Some(CONFIG_PATH));
+        assert_eq!(field(fields, "status").as_deref(), Some("500"));
+
+        let events = subscriber.events.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            field(&events[0], "message").as_deref(),
+            Some("request failed")
+        );
+    }
+
+    #[test]
+    fn test_poll_timed() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_delay(Duration::from_millis(100));
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let (data, latency) = client.poll_timed().unwrap();
+        assert_eq!(data, sample());
+        assert!(latency >= Duration::from_millis(100));
+
+        let (config, latency) = client.config_timed().unwrap();
+        assert_eq!(config.device_id, "awair-element_1234");
+        assert!(latency >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_https_base() {
+        let client = Awair::new("https://awair.example.com/").unwrap();
+        assert_eq!(client.api_base().scheme(), "https");
+
+        assert!(Awair::builder("https://192.168.1.10")
+            .danger_accept_invalid_certs(true)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_proxy() {
+        let proxy = MockAwair::start().unwrap();
+        let client = Awair::builder("http://awair.invalid")
+            .proxy(reqwest::Proxy::http(proxy.api_base()).unwrap())
+            .build()
+            .unwrap();
+
+        // The mock isn't a real proxy, but it sees the proxied request.
+        let _ = client.poll();
+        assert_eq!(
+            proxy.requests()[0].path,
+            "http://awair.invalid/air-data/latest"
+        );
+
+        assert!(Awair::builder("http://192.168.1.10")
+            .proxy(reqwest::Proxy::all("http://proxy.example.com:3128").unwrap())
+            .no_proxy()
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_from_env() {
+        // NOTE: These variables are process-wide, so everything that touches
+        // them lives in this one test.
+        for var in [BASE_URL_VAR, HOST_VAR, PORT_VAR] {
+            std::env::remove_var(var);
+        }
+
+        assert!(matches!(
+            Awair::from_env(),
+            Err(Error::Env {
+                var: BASE_URL_VAR,
+                ..
+            })
+        ));
+
+        std::env::set_var(HOST_VAR, "awair.local");
+        assert_eq!(
+            Awair::from_env().unwrap().api_base().as_str(),
+            "http://awair.local/"
+        );
+
+        std::env::set_var(PORT_VAR, "8080");
+        assert_eq!(
+            Awair::from_env().unwrap().api_base().as_str(),
+            "http://awair.local:8080/"
+        );
+
+        std::env::set_var(PORT_VAR, "eighty");
+        assert!(matches!(
+            Awair::from_env(),
+            Err(Error::InvalidEnv { var: PORT_VAR, value }) if value == "eighty"
+        ));
+
+        // The base URL takes precedence over the host and port.
+        std::env::set_var(BASE_URL_VAR, "http://192.168.1.10");
+        assert_eq!(
+            Awair::from_env().unwrap().api_base().as_str(),
+            "http://192.168.1.10/"
+        );
+
+        std::env::set_var(BASE_URL_VAR, "mailto:foo");
+        assert!(matches!(
+            Awair::from_env(),
+            Err(Error::UnsupportedScheme(_))
+        ));
+
+        for var in [BASE_URL_VAR, HOST_VAR, PORT_VAR] {
+            std::env::remove_var(var);
+        }
+    }
+
+    #[test]
+    fn test_omni_sensors() {
+        // The mock serves an Element payload, without light or sound readings.
+        let element = sample();
+        assert_eq!(element.lux, None);
+        assert_eq!(element.spl_a, None);
+
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        payload["lux"] = serde_json::json!(241.7);
+        payload["spl_a"] = serde_json::json!(48.5);
+        let omni: AirData = serde_json::from_value(payload).unwrap();
+        assert_eq!(omni.lux, Some(241.7));
+        assert_eq!(omni.spl_a, Some(48.5));
+        assert!(omni.extra.is_empty());
+    }
+
+    #[test]
+    fn test_score_range() {
+        let with_score = |score: serde_json::Value| {
+            let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+            payload["score"] = score;
+            serde_json::from_value::<AirData>(payload)
+        };
+
+        assert_eq!(with_score(serde_json::json!(0)).unwrap().score, 0);
+        assert_eq!(with_score(serde_json::json!(100)).unwrap().score, 100);
+
+        for score in [
+            serde_json::json!(101),
+            serde_json::json!(-1),
+            serde_json::json!(256),
+        ] {
+            let err = with_score(score).unwrap_err();
+            assert!(err.to_string().contains("an Awair Score from 0-100"));
+        }
+    }
+
+    #[test]
+    fn test_poll_loop() {
+        let mock = MockAwair::start().unwrap();
+        mock.queue_response(MockResponse::new(500, ""));
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let mut results = Vec::new();
+        client.poll_loop(MIN_STREAM_INTERVAL, |sample| {
+            results.push(sample);
+            if results.len() == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        // The error is delivered to the callback, without ending the loop.
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_err());
+        assert!(results[1..].iter().all(Result::is_ok));
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_display_modes() {
+        for (wire, mode) in [
+            ("score", DisplayMode::Score),
+            ("temp", DisplayMode::Temperature),
+            ("humid", DisplayMode::Humidity),
+            ("co2", DisplayMode::Co2),
+            ("voc", DisplayMode::Voc),
+            ("pm25", DisplayMode::Pm25),
+            ("clock", DisplayMode::Clock),
+            ("aqi", DisplayMode::Unknown("aqi".into())),
+        ] {
+            let json = serde_json::json!(wire);
+            assert_eq!(
+                serde_json::from_value::<DisplayMode>(json.clone()).unwrap(),
+                mode
+            );
+            assert_eq!(serde_json::to_value(&mode).unwrap(), json);
+            assert_eq!(mode.to_string(), wire);
+        }
+    }
+
+    #[test]
+    fn test_led_modes() {
+        for (wire, mode) in [
+            ("auto", LedMode::Auto),
+            ("manual", LedMode::Manual),
+            ("sleep", LedMode::Sleep),
+            ("party", LedMode::Unknown("party".into())),
+        ] {
+            let json = serde_json::json!(wire);
+            assert_eq!(
+                serde_json::from_value::<LedMode>(json.clone()).unwrap(),
+                mode
+            );
+            assert_eq!(serde_json::to_value(&mode).unwrap(), json);
+            assert_eq!(mode.to_string(), wire);
+        }
+
+        // An unrecognized mode doesn't break parsing the whole config.
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+        payload["led"]["mode"] = serde_json::json!("party");
+        payload["display"] = serde_json::json!("aqi");
+        let parsed: DeviceConfig = serde_json::from_value(payload).unwrap();
+        assert_eq!(parsed.led.mode, LedMode::Unknown("party".into()));
+        assert_eq!(parsed.display, DisplayMode::Unknown("aqi".into()));
+    }
+
+    #[test]
+    fn test_unexpected_content_type() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        mock.queue_response(
+            MockResponse::new(200, "<html><body>Setup</body></html>")
+                .header("Content-Type", "text/html; charset=utf-8"),
+        );
+        match client.poll().unwrap_err().without_endpoint() {
+            Error::UnexpectedContentType { content_type } => {
+                assert_eq!(content_type, "text/html; charset=utf-8")
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        // JSON with parameters is still JSON.
+        mock.queue_response(
+            MockResponse::new(200, SAMPLE_AIR_DATA)
+                .header("Content-Type", "application/json; charset=utf-8"),
+        );
+        assert!(client.poll().is_ok());
+    }
+
+    #[test]
+    fn test_poll_if_new() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let first = client.poll_if_new(None).unwrap().unwrap();
+        assert!(client
+            .poll_if_new(Some(&first.timestamp))
+            .unwrap()
+            .is_none());
+
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        payload["timestamp"] = serde_json::json!("2024-01-01T00:00:10.000Z");
+        mock.queue_response(MockResponse::json(&payload));
+        let second = client.poll_if_new(Some(&first.timestamp)).unwrap().unwrap();
+        assert_eq!(
+            second.timestamp - first.timestamp,
+            chrono::Duration::seconds(10)
+        );
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::builder(&mock.api_base())
+            .min_interval(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let start = Instant::now();
+        let mut completed = Vec::new();
+        for _ in 0..3 {
+            client.poll().unwrap();
+            completed.push(start.elapsed());
+        }
+
+        // The first poll isn't limited, but each later poll waits out the interval.
+        assert!(completed[0] < Duration::from_millis(200));
+        assert!(completed[1] >= Duration::from_millis(200));
+        assert!(completed[2] >= Duration::from_millis(400));
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::builder(&mock.api_base())
+            .cache_ttl(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        // A hit within the TTL doesn't contact the device.
+        client.poll().unwrap();
+        client.poll().unwrap();
+        assert_eq!(mock.requests().len(), 1);
+
+        // Clones share the cache.
+        client.clone().poll().unwrap();
+        assert_eq!(mock.requests().len(), 1);
+
+        client.invalidate_cache();
+        client.poll().unwrap();
+        assert_eq!(mock.requests().len(), 2);
+
+        std::thread::sleep(Duration::from_millis(250));
+        client.poll().unwrap();
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::builder(&mock.api_base())
+            .timeout(Duration::from_millis(100))
+            .circuit_breaker(2, Duration::from_millis(300))
+            .build()
+            .unwrap();
+
+        for _ in 0..2 {
+            mock.queue_response(MockResponse::new(200, "").delay(Duration::from_secs(1)));
+            assert!(client.poll().unwrap_err().is_transient());
+        }
+
+        // While open, the device isn't contacted.
+        assert!(matches!(
+            client.poll().unwrap_err().without_endpoint(),
+            Error::CircuitOpen
+        ));
+        assert_eq!(mock.requests().len(), 2);
+
+        std::thread::sleep(Duration::from_millis(350));
+        assert!(client.poll().is_ok());
+        assert!(client.poll().is_ok());
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[test]
+    fn test_clock_skew() {
+        let data = sample();
+        assert_eq!(
+            data.clock_skew(data.timestamp - chrono::Duration::seconds(30)),
+            chrono::Duration::seconds(30)
+        );
+        assert_eq!(
+            data.clock_skew(data.timestamp + chrono::Duration::seconds(30)),
+            chrono::Duration::seconds(-30)
+        );
+
+        // A device whose clock runs about 90 seconds fast.
+        let mock = MockAwair::start().unwrap();
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        payload["timestamp"] =
+            serde_json::json!((Utc::now() + chrono::Duration::seconds(90)).to_rfc3339());
+        mock.queue_response(MockResponse::json(&payload));
+
+        let client = Awair::new(&mock.api_base()).unwrap();
+        let (_, skew) = client.poll_with_skew().unwrap();
+        assert!((skew - chrono::Duration::seconds(90)).num_seconds().abs() <= 5);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_gzip_response() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(SAMPLE_AIR_DATA.as_bytes()).unwrap();
+        let body = encoder.finish().unwrap();
+
+        let mock = MockAwair::start().unwrap();
+        mock.queue_response(
+            MockResponse::new(200, body)
+                .header("Content-Type", "application/json")
+                .header("Content-Encoding", "gzip"),
+        );
+
+        let client = Awair::new(&mock.api_base()).unwrap();
+        assert_eq!(client.poll().unwrap(), sample());
+        assert!(mock.requests()[0]
+            .header("accept-encoding")
+            .is_some_and(|encodings| encodings.contains("gzip")));
+    }
+
+    #[test]
+    fn test_lenient_numbers() {
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        for (key, value) in [
+            ("score", "90"),
+            ("temp", "21.4"),
+            ("co2", " 612 "),
+            ("pm25", "3"),
+            ("co2_est", "450"),
+        ] {
+            payload[key] = serde_json::json!(value);
+        }
+        assert_eq!(
+            serde_json::from_value::<AirData>(payload.clone()).unwrap(),
+            sample()
+        );
+
+        payload["co2"] = serde_json::json!("lots");
+        let err = serde_json::from_value::<AirData>(payload).unwrap_err();
+        assert!(err.to_string().contains("expected a number"));
+    }
+
+    #[test]
+    fn test_ping() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&mock.api_base()).unwrap();
+        assert!(client.ping().unwrap());
+
+        mock.queue_response(MockResponse::new(500, ""));
+        assert!(!client.ping().unwrap());
+
+        // An unparseable body doesn't matter.
+        mock.queue_response(MockResponse::new(200, "not json"));
+        assert!(client.ping().unwrap());
+
+        let client = Awair::new(&unreachable_base()).unwrap();
+        assert!(matches!(
+            client.ping().unwrap_err().without_endpoint(),
+            Error::Connect(_)
+        ));
+    }
+
+    #[test]
+    fn test_failover() {
+        let live = MockAwair::start().unwrap();
+        let dead = unreachable_base();
+        let client = Awair::with_fallbacks(&[&dead, &live.api_base()]).unwrap();
+
+        assert_eq!(client.poll().unwrap().score, 90);
+        // The live base is remembered, and tried first next time.
+        assert_eq!(client.config().unwrap().device_id, "awair-element_1234");
+        assert_eq!(live.requests().len(), 2);
+
+        // HTTP errors don't trigger a failover.
+        let primary = MockAwair::start().unwrap();
+        let fallback = MockAwair::start().unwrap();
+        primary.queue_response(MockResponse::new(404, ""));
+        let client = Awair::with_fallbacks(&[&primary.api_base(), &fallback.api_base()]).unwrap();
+        assert!(client.poll().is_err());
+        assert!(fallback.requests().is_empty());
+    }
+
+    #[test]
+    fn test_no_bases() {
+        assert!(matches!(Awair::with_fallbacks(&[]), Err(Error::NoBases)));
+    }
+
+    /// A logger that records this crate's log records, along with the thread
+    /// that each was emitted on.
+    #[cfg(feature = "log")]
+    struct CapturingLogger {
+        records: Mutex<Vec<(std::thread::ThreadId, log::Level, String)>>,
+    }
+
+    #[cfg(feature = "log")]
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+            metadata
+                .target()
+                .starts_with(module_path!().trim_end_matches("::tests"))
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            if !self.enabled(record.metadata()) {
+                return;
+            }
+
+            self.records.lock().unwrap().push((
+                std::thread::current().id(),
+                record.level(),
+                record.args().to_string(),
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_log_records() {
+        static LOGGER: CapturingLogger = CapturingLogger {
+            records: Mutex::new(Vec::new()),
+        };
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(CONFIG_PATH, MockResponse::new(500, ""));
+        let client = Awair::new(&mock.api_base()).unwrap();
+        client.poll().unwrap();
+        client.config().unwrap_err();
+
+        let size = serde_json::from_str::<serde_json::Value>(SAMPLE_AIR_DATA)
+            .unwrap()
+            .to_string()
+            .len();
+
+        // NOTE: The logger is process-wide, so only this thread's records count.
+        let records = LOGGER
+            .records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(thread, ..)| *thread == std::thread::current().id())
+            .map(|(_, level, message)| (*level, message.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records[0],
+            (
+                log::Level::Debug,
+                format!("GET {}air-data/latest", client.api_base())
+            )
+        );
+        assert_eq!(
+            records[1],
+            (
+                log::Level::Trace,
+                format!("{LATEST_PATH}: {size} byte response")
+            )
+        );
+        assert_eq!(records[2].0, log::Level::Debug);
+        assert_eq!(records[3].0, log::Level::Trace);
+        assert_eq!(
+            records[4],
+            (
+                log::Level::Warn,
+                format!("request to {CONFIG_PATH} failed: device responded with HTTP 500: ")
+            )
+        );
+    }
+
+    #[test]
+    fn test_error_endpoint() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(CONFIG_PATH, MockResponse::new(503, "busy"));
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let err = client.config().unwrap_err();
+        assert_eq!(err.endpoint(), Some("/settings/config/data"));
+        assert!(matches!(err.without_endpoint(), Error::HttpStatus { .. }));
+
+        // The message includes both the path and the underlying cause.
+        let message = err.to_string();
+        assert!(message.starts_with("request to /settings/config/data failed: "));
+        assert!(message.ends_with(&err.without_endpoint().to_string()));
+
+        // Errors that don't come from a request have no endpoint.
+        assert_eq!(Error::NoBases.endpoint(), None);
+    }
+
+    #[test]
+    fn test_user_agent() {
+        let mock = MockAwair::start().unwrap();
+        Awair::new(&mock.api_base()).unwrap().poll().unwrap();
+        Awair::builder(&mock.api_base())
+            .user_agent("my-monitor/1.0")
+            .build()
+            .unwrap()
+            .poll()
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].header("user-agent"), Some(DEFAULT_USER_AGENT));
+        assert!(DEFAULT_USER_AGENT.starts_with("awair-local-api-rs/"));
+        assert_eq!(requests[1].header("user-agent"), Some("my-monitor/1.0"));
+    }
+
+    #[test]
+    fn test_verify_display_mode() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let applied = client.set_display_mode(DisplayMode::Co2).unwrap();
+        assert_eq!(applied.display, DisplayMode::Co2);
+        assert!(client.verify_display_mode(DisplayMode::Co2).unwrap());
+
+        // A device that silently ignores the change keeps serving its old config.
+        let stale = MockAwair::start().unwrap();
+        stale.set_response(
+            CONFIG_PATH,
+            MockResponse::json(&serde_json::from_str(SAMPLE_CONFIG).unwrap()),
+        );
+        let client = Awair::new(&stale.api_base()).unwrap();
+
+        let applied = client.set_display_mode(DisplayMode::Co2).unwrap();
+        assert_eq!(applied.display, DisplayMode::Score);
+        assert!(!client.verify_display_mode(DisplayMode::Co2).unwrap());
+        assert!(client.verify_display_mode(DisplayMode::Score).unwrap());
+    }
+
+    #[test]
+    fn test_score_breakdown() {
+        assert_eq!(sample().score_breakdown, None);
+
+        let mut payload: serde_json::Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        payload["score_breakdown"] = serde_json::json!({
+            "temp": 2.5,
+            "humid": "1.5",
+            "co2": 4,
+            "voc": 2.0,
+        });
+        let data: AirData = serde_json::from_value(payload).unwrap();
+        assert_eq!(
+            data.score_breakdown,
+            Some(ScoreBreakdown {
+                temperature: 2.5,
+                humidity: 1.5,
+                co2: 4.0,
+                voc: 2.0,
+                pm25: None,
+            })
+        );
+        assert!(data.extra.is_empty());
+    }
+
+    #[test]
+    fn test_hard_timeout() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_delay(Duration::from_secs(2));
+
+        // No timeout of its own.
+        let http = reqwest::blocking::Client::builder()
+            .timeout(None)
+            .build()
+            .unwrap();
+        let client = Awair::builder(&mock.api_base())
+            .client(http)
+            .hard_timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+
+        let start = Instant::now();
+        let err = client.poll().unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(matches!(err.without_endpoint(), Error::Timeout(None)));
+        assert_eq!(err.endpoint(), Some(LATEST_PATH));
+
+        // Requests that complete in time are unaffected.
+        mock.set_delay(Duration::ZERO);
+        assert!(client.poll().is_ok());
+    }
+
+    #[test]
+    fn test_to_flat_map() {
+        let mut data = sample();
+        data.extra
+            .insert("radon".into(), serde_json::json!({ "value": 1 }));
+        let map = data.to_flat_map().unwrap();
+
+        let mut keys = map.keys().map(String::as_str).collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "abs_humid",
+                "co2",
+                "co2_est",
+                "co2_est_baseline",
+                "dew_point",
+                "humid",
+                "pm10_est",
+                "pm25",
+                "score",
+                "temp",
+                "timestamp",
+                "voc",
+                "voc_baseline",
+                "voc_ethanol_raw",
+                "voc_h2_raw",
+            ]
+        );
+
+        let timestamp = map["timestamp"].as_str().unwrap();
+        assert_eq!(
+            DateTime::parse_from_rfc3339(timestamp).unwrap(),
+            data.timestamp
+        );
+        assert_eq!(map["temp"], serde_json::json!(21.4));
+        assert_eq!(map["co2"], serde_json::json!(612));
+        assert!(map["score"].is_u64());
+    }
+
+    #[test]
+    fn test_min_firmware() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::builder(&mock.api_base())
+            .min_firmware(FIVE_MIN_AVG_PATH, semver::Version::new(1, 0, 0))
+            // Replaces the requirement above; the mock runs 1.2.4.
+            .min_firmware(FIVE_MIN_AVG_PATH, semver::Version::new(2, 0, 0))
+            .min_firmware(FIFTEEN_MIN_AVG_PATH, semver::Version::new(1, 2, 4))
+            .build()
+            .unwrap();
+
+        // Until the firmware version is known, requests are made as usual.
+        assert!(client.five_minute_average().is_ok());
+
+        client.config().unwrap();
+        match client.five_minute_average().unwrap_err().without_endpoint() {
+            Error::UnsupportedByFirmware { endpoint, required } => {
+                assert_eq!(endpoint, FIVE_MIN_AVG_PATH);
+                assert_eq!(*required, semver::Version::new(2, 0, 0));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert_eq!(
+            mock.requests()
+                .iter()
+                .filter(|request| request.path == FIVE_MIN_AVG_PATH)
+                .count(),
+            1
+        );
+
+        // New enough firmware proceeds.
+        assert!(client.fifteen_minute_average().is_ok());
+    }
+
+    #[test]
+    fn test_default_min_firmware() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        // The mock runs 1.2.4, which serves the averaged endpoints.
+        client.config().unwrap();
+        assert!(client.five_minute_average().is_ok());
+
+        let mut config = config();
+        config.firmware_version = "1.1.0".into();
+        mock.set_config(&config);
+        client.config().unwrap();
+        for path in [FIVE_MIN_AVG_PATH, FIFTEEN_MIN_AVG_PATH] {
+            let err = match path {
+                FIVE_MIN_AVG_PATH => client.five_minute_average(),
+                _ => client.fifteen_minute_average(),
+            }
+            .unwrap_err();
+            assert!(matches!(
+                err.without_endpoint(),
+                Error::UnsupportedByFirmware { endpoint, required }
+                    if endpoint == path && *required == semver::Version::new(1, 2, 4)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_custom_paths() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(
+            "/v2/air",
+            MockResponse::json(&serde_json::from_str(SAMPLE_AIR_DATA).unwrap()),
+        );
+        mock.set_response(
+            "/v2/config",
+            MockResponse::json(&serde_json::from_str(SAMPLE_CONFIG).unwrap()),
+        );
+
+        let client = Awair::builder(&mock.api_base())
+            .latest_path("/v2/air")
+            .config_path("v2/config")
+            .build()
+            .unwrap();
+        assert_eq!(client.poll().unwrap(), sample());
+        assert_eq!(client.config().unwrap(), config());
+
+        let paths = mock
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["/v2/air", "/v2/config"]);
+    }
+
+    #[test]
+    fn test_try_from() {
+        let client: Awair = "http://192.168.1.10".try_into().unwrap();
+        assert_eq!(client.api_base().as_str(), "http://192.168.1.10/");
+
+        assert!(matches!(
+            Awair::try_from("ftp://192.168.1.10"),
+            Err(Error::UnsupportedScheme(_))
+        ));
+
+        let url = url::Url::parse("http://awair.local/prefix").unwrap();
+        let client = Awair::try_from(url).unwrap();
+        assert_eq!(client.api_base().as_str(), "http://awair.local/prefix/");
+
+        let url = url::Url::parse("mailto:foo@example.com").unwrap();
+        assert!(matches!(
+            Awair::try_from(url),
+            Err(Error::UnsupportedScheme(_))
+        ));
+    }
+
+    #[test]
+    fn test_clone_across_threads() {
+        let mock = MockAwair::start().unwrap();
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let handles = (0..4)
+            .map(|_| {
+                let client = client.clone();
+                std::thread::spawn(move || client.poll().map(|data| data.score))
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap().unwrap(), 90);
+        }
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[test]
+    fn test_to_update_body() {
+        let mut config = config();
+        assert_eq!(
+            config.to_update_body().unwrap(),
+            serde_json::json!({
+                "display": "score",
+                "led": { "mode": "auto", "brightness": 100 },
+            })
+        );
+
+        config.display = DisplayMode::Clock;
+        config.led.mode = LedMode::Sleep;
+        config.knocking = Some(true);
+        let body = config.to_update_body().unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "display": "clock",
+                "led": { "mode": "sleep", "brightness": 100 },
+                "knocking": "on",
+            })
+        );
+
+        // Pushing it back sends exactly that body.
+        let mock = MockAwair::start().unwrap();
+        mock.awair().unwrap().apply_config(&config).unwrap();
+        assert_eq!(json_body(&mock.requests()[0]), body);
+    }
+
+    #[test]
+    fn test_connect() {
+        for (input, expected) in [
+            ("192.168.1.10", "http://192.168.1.10/"),
+            (" awair.local:8080 ", "http://awair.local:8080/"),
+            ("awair.local/prefix", "http://awair.local/prefix/"),
+            (
+                "https://awair.example.com/awair",
+                "https://awair.example.com/awair/",
+            ),
+            ("fe80::1", "http://[fe80::1]/"),
+            ("http://[::1]:8080", "http://[::1]:8080/"),
+        ] {
+            assert_eq!(
+                Awair::connect(input).unwrap().api_base().as_str(),
+                expected,
+                "{input}"
+            );
+        }
+
+        assert!(matches!(
+            Awair::connect("ftp://awair.local"),
+            Err(Error::UnsupportedScheme(_))
+        ));
+    }
+
+    #[test]
+    fn test_estimate_update_interval() {
+        let mock = MockAwair::start().unwrap();
+        let mut first = sample();
+        first.timestamp = "2024-01-01T00:00:00Z".parse().unwrap();
+        let mut next = sample();
+        next.timestamp = "2024-01-01T00:00:10Z".parse().unwrap();
+        for air_data in [&first, &first, &next] {
+            mock.queue_response(MockResponse::json(&serde_json::to_value(air_data).unwrap()));
+        }
+
+        let interval = mock.awair().unwrap().estimate_update_interval().unwrap();
+        assert_eq!(interval, Duration::from_secs(10));
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[test]
+    fn test_voc_ug_per_m3() {
+        let mut air_data = sample();
+        air_data.voc = 1000;
+
+        // 1000 ppb of toluene at 25°C and 1 atm: 1000 × 92.14 / 24.465 L/mol.
+        let at_25 = air_data.voc_ug_per_m3_at(92.14, 25.0);
+        assert!((at_25 - 3766.13).abs() < 0.1, "{at_25}");
+
+        // Warmer air is less dense, so the same mixing ratio is less mass.
+        assert!(air_data.voc_ug_per_m3_at(92.14, 35.0) < at_25);
+
+        // By default, the sample's own temperature is used.
+        assert_eq!(
+            air_data.voc_ug_per_m3(92.14),
+            air_data.voc_ug_per_m3_at(92.14, air_data.temperature)
+        );
+
+        air_data.voc = 0;
+        assert_eq!(air_data.voc_ug_per_m3(92.14), 0.0);
+    }
+
+    #[test]
+    fn test_is_warming_up() {
+        assert!(!sample().is_warming_up());
+
+        // Freshly powered on: no baseline yet, and a nonsense CO2 reading.
+        let mut warming = sample();
+        warming.voc_baseline = 0;
+        warming.co2 = 0;
+        assert!(warming.is_warming_up());
+
+        let warmups: [fn(&mut AirData); 5] = [
+            |s| s.voc_baseline = 0,
+            |s| s.estimated_co2_baseline = Some(0),
+            |s| s.co2 = 0,
+            |s| s.co2 = 5001,
+            |s| s.voc = 60001,
+        ];
+        for warmup in warmups {
+            let mut air_data = sample();
+            warmup(&mut air_data);
+            assert!(air_data.is_warming_up(), "{air_data:?}");
+        }
+
+        // Readings at the edge of the rated range are still plausible.
+        let mut edge = sample();
+        edge.co2 = 5000;
+        edge.voc = 60000;
+        assert!(!edge.is_warming_up());
+    }
+
+    #[test]
+    fn test_export_import_config() {
+        let source = MockAwair::start().unwrap();
+        let mut original = config();
+        original.device_id = "awair-element_5678".into();
+        original.ip = "192.168.1.20".into();
+        original.display = DisplayMode::Co2;
+        original.led = LedConfig {
+            mode: LedMode::Manual,
+            brightness: 40,
+        };
+        original.knocking = Some(true);
+        source.set_config(&original);
+
+        let target = MockAwair::start().unwrap();
+        let exported = source.awair().unwrap().export_config().unwrap();
+        let imported = target.awair().unwrap().import_config(&exported).unwrap();
+
+        assert_eq!(imported.display, original.display);
+        assert_eq!(imported.led, original.led);
+        assert_eq!(imported.knocking, original.knocking);
+
+        // The identity and network settings stay the target's own.
+        assert_eq!(imported.device_id, config().device_id);
+        assert_eq!(imported.ip, config().ip);
+
+        let requests = target.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "PUT");
+        assert_eq!(json_body(&requests[0]), exported.to_update_body().unwrap());
+    }
+
+    #[test]
+    fn test_poll_labeled() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        for _ in 0..3 {
+            let labeled = client.poll_labeled().unwrap();
+            assert_eq!(labeled.device_id, config().device_id);
+            assert_eq!(labeled.data, sample());
+        }
+
+        // The device ID is fetched once, and reused for subsequent polls.
+        let paths = mock
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "/settings/config/data",
+                "/air-data/latest",
+                "/air-data/latest",
+                "/air-data/latest",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_brightness_percent() {
+        let led = |brightness| LedConfig {
+            mode: LedMode::Manual,
+            brightness,
+        };
+
+        assert_eq!(led(LedConfig::MIN_BRIGHTNESS).brightness_percent(), 0.0);
+        assert_eq!(led(40).brightness_percent(), 40.0);
+        assert_eq!(led(LedConfig::MAX_BRIGHTNESS).brightness_percent(), 100.0);
+        // Out-of-range brightnesses reported by the device are clamped.
+        assert_eq!(led(250).brightness_percent(), 100.0);
+    }
+
+    #[test]
+    fn test_brightness_validation() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        client.set_led(LedMode::Manual, 0).unwrap();
+        client.set_led(LedMode::Manual, 100).unwrap();
+        assert_eq!(mock.requests().len(), 2);
+
+        assert!(matches!(
+            client.set_led(LedMode::Manual, 101),
+            Err(Error::InvalidBrightness(101))
+        ));
+
+        let mut edited = config();
+        edited.led.brightness = 101;
+        assert!(matches!(
+            client.apply_config(&edited),
+            Err(Error::InvalidBrightness(101))
+        ));
+
+        // Invalid brightnesses are rejected without contacting the device.
+        assert_eq!(mock.requests().len(), 2);
     }
 }
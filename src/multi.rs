@@ -0,0 +1,135 @@
+//! Polling multiple Awair devices at once.
+
+use std::{panic, thread};
+
+use crate::{AirData, Awair, Error};
+
+#[cfg(feature = "async")]
+use crate::AwairAsync;
+
+/// A collection of [`Awair`] clients, polled together.
+#[derive(Debug)]
+pub struct MultiAwair {
+    devices: Vec<Awair>,
+}
+
+impl MultiAwair {
+    /// Create a new collection from the given clients.
+    pub fn new(devices: Vec<Awair>) -> Self {
+        Self { devices }
+    }
+
+    /// Returns the clients in this collection.
+    pub fn devices(&self) -> &[Awair] {
+        &self.devices
+    }
+
+    /// Poll every device for its latest air quality data, concurrently.
+    ///
+    /// Each device is polled on its own thread, and each device's result is
+    /// returned independently (alongside its index in the collection), so
+    /// that one unreachable device doesn't prevent the others from being read.
+    pub fn poll_all(&self) -> Vec<(usize, Result<AirData, Error>)> {
+        thread::scope(|scope| {
+            let handles = self
+                .devices
+                .iter()
+                .map(|device| scope.spawn(|| device.poll()))
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .enumerate()
+                .collect()
+        })
+    }
+}
+
+impl From<Vec<Awair>> for MultiAwair {
+    fn from(devices: Vec<Awair>) -> Self {
+        Self::new(devices)
+    }
+}
+
+/// A collection of [`AwairAsync`] clients, polled together.
+#[cfg(feature = "async")]
+#[derive(Clone, Debug)]
+pub struct MultiAwairAsync {
+    devices: Vec<AwairAsync>,
+}
+
+#[cfg(feature = "async")]
+impl MultiAwairAsync {
+    /// Create a new collection from the given clients.
+    pub fn new(devices: Vec<AwairAsync>) -> Self {
+        Self { devices }
+    }
+
+    /// Returns the clients in this collection.
+    pub fn devices(&self) -> &[AwairAsync] {
+        &self.devices
+    }
+
+    /// Poll every device for its latest air quality data, concurrently.
+    ///
+    /// As with [`MultiAwair::poll_all`], each device's result is returned
+    /// independently, alongside its index in the collection.
+    pub async fn poll_all(&self) -> Vec<(usize, Result<AirData, Error>)> {
+        futures_util::future::join_all(self.devices.iter().map(AwairAsync::poll))
+            .await
+            .into_iter()
+            .enumerate()
+            .collect()
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<Vec<AwairAsync>> for MultiAwairAsync {
+    fn from(devices: Vec<AwairAsync>) -> Self {
+        Self::new(devices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockAwair, MockResponse};
+
+    #[test]
+    fn test_poll_all() {
+        let healthy = MockAwair::start().unwrap();
+        let failing = MockAwair::start().unwrap();
+        failing.set_response("/air-data/latest", MockResponse::new(500, ""));
+
+        let multi = MultiAwair::new(vec![
+            Awair::new(&healthy.api_base()).unwrap(),
+            Awair::new(&failing.api_base()).unwrap(),
+        ]);
+
+        let results = multi.poll_all();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, 0);
+        assert_eq!(results[0].1.as_ref().unwrap().score, 90);
+        assert_eq!(results[1].0, 1);
+        assert!(results[1].1.is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_poll_all_async() {
+        let healthy = MockAwair::start().unwrap();
+        let failing = MockAwair::start().unwrap();
+        failing.set_response("/air-data/latest", MockResponse::new(500, ""));
+
+        let multi = MultiAwairAsync::new(vec![
+            AwairAsync::new(&healthy.api_base()).unwrap(),
+            AwairAsync::new(&failing.api_base()).unwrap(),
+        ]);
+
+        let results = multi.poll_all().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].1.as_ref().unwrap().score, 90);
+        assert!(results[1].1.is_err());
+    }
+}
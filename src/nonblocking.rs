@@ -0,0 +1,887 @@
+//! An asynchronous client for the Awair Local API.
+
+use std::future::Future;
+use std::net::IpAddr;
+use std::ops::ControlFlow;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures_util::{stream, Stream, StreamExt};
+use reqwest::{Request, Response};
+use tokio::time::{self, MissedTickBehavior};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "log")]
+use crate::log_response_size;
+#[cfg(feature = "chrono-tz")]
+use crate::tz::{cache_timezone, cached_timezone};
+
+use crate::{
+    cache_device_id, cached_device_id, check_content_type, check_firmware, default_min_firmware,
+    endpoint_url, host_port_base, loose_base, parse_base, partial, remember_firmware, status_error,
+    update_interval, AirData, Capabilities, DeviceConfig, DisplayMode, DisplayUpdate, Error,
+    FieldError, KnockingUpdate, LabeledAirData, LedMode, LedUpdate, CONFIG_PATH, DEFAULT_TIMEOUT,
+    DEFAULT_USER_AGENT, ESTIMATE_POLL_GAP, ESTIMATE_TIMEOUT, FIFTEEN_MIN_AVG_PATH,
+    FIVE_MIN_AVG_PATH, LATEST_PATH, MIN_STREAM_INTERVAL, RAW_PATH,
+};
+
+/// Represents an asynchronous connection to an Awair device.
+///
+/// This is the non-blocking counterpart to [`Awair`](crate::Awair), and
+/// exposes the same endpoints as `async` methods. Cloning an `AwairAsync`
+/// is cheap, and clones share the same underlying connection pool.
+///
+/// ```no_run
+/// use awair_local_api::AwairAsync;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), awair_local_api::Error> {
+///     let client = AwairAsync::new("http://192.168.1.10")?;
+///
+///     println!("{:#?}", client.config().await?);
+///     println!("{:#?}", client.poll().await?);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AwairAsync {
+    api_base: url::Url,
+    http: HttpClient,
+    timeout: Option<Duration>,
+    latest_path: String,
+    config_path: String,
+    firmware: Arc<Mutex<Option<semver::Version>>>,
+    min_firmware: Arc<[(String, semver::Version)]>,
+    device_id: Arc<Mutex<Option<String>>>,
+    #[cfg(feature = "chrono-tz")]
+    timezone: Arc<Mutex<Option<chrono_tz::Tz>>>,
+}
+
+impl AwairAsync {
+    /// Create a new asynchronous client capable of talking to an Awair's Local API.
+    ///
+    /// The client uses a total request timeout of [`DEFAULT_TIMEOUT`], which
+    /// can be changed with [`AwairAsync::timeout`].
+    pub fn new(api_base: &str) -> Result<Self, Error> {
+        let client = reqwest::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .timeout(DEFAULT_TIMEOUT)
+            .build()?;
+
+        Self::with_client(api_base, client)
+    }
+
+    /// Create a new asynchronous client that uses the given `reqwest` client
+    /// for its requests.
+    ///
+    /// As with [`Awair::with_client`](crate::Awair::with_client), the caller
+    /// owns the supplied client's configuration.
+    pub fn with_client(api_base: &str, client: reqwest::Client) -> Result<Self, Error> {
+        Self::with_http(api_base, HttpClient::Plain(client))
+    }
+
+    /// Create a new asynchronous client that sends its requests through the
+    /// given `reqwest-middleware` client, so that its middleware (e.g. for
+    /// tracing or retries) applies to them.
+    ///
+    /// As with [`AwairAsync::with_client`], the caller owns the supplied
+    /// client's configuration.
+    #[cfg(feature = "middleware")]
+    pub fn with_middleware_client(
+        api_base: &str,
+        client: reqwest_middleware::ClientWithMiddleware,
+    ) -> Result<Self, Error> {
+        Self::with_http(api_base, HttpClient::Middleware(client))
+    }
+
+    /// Create a new asynchronous client that sends its requests through the
+    /// given HTTP client.
+    fn with_http(api_base: &str, http: HttpClient) -> Result<Self, Error> {
+        Ok(Self {
+            api_base: parse_base(api_base)?,
+            http,
+            timeout: None,
+            latest_path: LATEST_PATH.into(),
+            config_path: CONFIG_PATH.into(),
+            firmware: Default::default(),
+            min_firmware: default_min_firmware().into(),
+            device_id: Default::default(),
+            #[cfg(feature = "chrono-tz")]
+            timezone: Default::default(),
+        })
+    }
+
+    /// Create a new asynchronous client for the Awair at the given IP address,
+    /// on the standard HTTP port.
+    pub fn from_addr(addr: IpAddr) -> Result<Self, Error> {
+        Self::from_host_port(&addr.to_string(), 80)
+    }
+
+    /// Create a new asynchronous client for the Awair at the given host (a
+    /// domain name or IP address) and port.
+    pub fn from_host_port(host: &str, port: u16) -> Result<Self, Error> {
+        Self::new(&host_port_base(host, port))
+    }
+
+    /// Create a new asynchronous client from a loosely formatted address.
+    ///
+    /// See [`Awair::connect`](crate::Awair::connect).
+    pub fn connect(input: &str) -> Result<Self, Error> {
+        Self::new(&loose_base(input))
+    }
+
+    /// Set the total timeout for each request, from connecting until the
+    /// response body has been read.
+    ///
+    /// This overrides the timeout of the underlying client, including one
+    /// supplied with [`AwairAsync::with_client`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Request the latest air quality sample from the given endpoint, instead
+    /// of `/air-data/latest`.
+    ///
+    /// See [`AwairBuilder::latest_path`](crate::AwairBuilder::latest_path).
+    pub fn latest_path(mut self, latest_path: &str) -> Self {
+        self.latest_path = latest_path.into();
+        self
+    }
+
+    /// Request and change the device's configuration at the given endpoint,
+    /// instead of `/settings/config/data`.
+    ///
+    /// See [`AwairBuilder::config_path`](crate::AwairBuilder::config_path).
+    pub fn config_path(mut self, config_path: &str) -> Self {
+        self.config_path = config_path.into();
+        self
+    }
+
+    /// Require at least the given firmware version for requests to the given
+    /// endpoint path, replacing any existing minimum for it.
+    ///
+    /// See [`AwairBuilder::min_firmware`](crate::AwairBuilder::min_firmware).
+    pub fn min_firmware(mut self, endpoint: &str, required: semver::Version) -> Self {
+        let mut min_firmware = self.min_firmware.to_vec();
+        min_firmware.retain(|(existing, _)| existing != endpoint);
+        min_firmware.push((endpoint.into(), required));
+        self.min_firmware = min_firmware.into();
+        self
+    }
+
+    /// Returns the Local API base URL that this client talks to.
+    pub fn api_base(&self) -> &url::Url {
+        &self.api_base
+    }
+
+    /// Returns the host (a domain name or IP address) that this client talks to.
+    pub fn host(&self) -> Option<&str> {
+        self.api_base.host_str()
+    }
+
+    /// Poll the Awair for its latest air quality data.
+    pub async fn poll(&self) -> Result<AirData, Error> {
+        self.get(&self.latest_path).await
+    }
+
+    /// Poll the Awair for its latest air quality data, labeled with the
+    /// device's ID.
+    ///
+    /// See [`Awair::poll_labeled`](crate::Awair::poll_labeled).
+    pub async fn poll_labeled(&self) -> Result<LabeledAirData, Error> {
+        let device_id = match cached_device_id(&self.device_id) {
+            Some(device_id) => device_id,
+            None => {
+                let device_id = self.config().await?.device_id;
+                cache_device_id(&self.device_id, &device_id);
+                device_id
+            }
+        };
+
+        let data = self.poll().await?;
+        Ok(LabeledAirData { device_id, data })
+    }
+
+    /// Poll the Awair for its latest air quality data, returning `None` if
+    /// the sample's timestamp is the same as `last`.
+    pub async fn poll_if_new(
+        &self,
+        last: Option<&DateTime<Utc>>,
+    ) -> Result<Option<AirData>, Error> {
+        let data = self.poll().await?;
+        Ok((last != Some(&data.timestamp)).then_some(data))
+    }
+
+    /// Like [`AwairAsync::poll`], but also return the device's clock skew
+    /// relative to the system time (see [`AirData::clock_skew`]).
+    pub async fn poll_with_skew(&self) -> Result<(AirData, chrono::Duration), Error> {
+        let data = self.poll().await?;
+        let skew = data.clock_skew(Utc::now());
+        Ok((data, skew))
+    }
+
+    /// Like [`AwairAsync::poll`], but also return how long the request took.
+    pub async fn poll_timed(&self) -> Result<(AirData, Duration), Error> {
+        let start = Instant::now();
+        self.poll().await.map(|data| (data, start.elapsed()))
+    }
+
+    /// Poll the Awair for its latest raw (uncalibrated) air quality data.
+    pub async fn poll_raw(&self) -> Result<AirData, Error> {
+        self.get(RAW_PATH).await
+    }
+
+    /// Poll the Awair for its air quality data, averaged over the last 5 minutes.
+    pub async fn five_minute_average(&self) -> Result<AirData, Error> {
+        self.get(FIVE_MIN_AVG_PATH).await
+    }
+
+    /// Poll the Awair for its air quality data, averaged over the last 15 minutes.
+    pub async fn fifteen_minute_average(&self) -> Result<AirData, Error> {
+        self.get(FIFTEEN_MIN_AVG_PATH).await
+    }
+
+    /// Estimate how often the Awair updates its latest sample.
+    ///
+    /// See [`Awair::estimate_update_interval`](crate::Awair::estimate_update_interval).
+    /// This uses [`tokio::time`], and so must be run within a Tokio runtime
+    /// with the time driver enabled.
+    pub async fn estimate_update_interval(&self) -> Result<Duration, Error> {
+        let start = Instant::now();
+        let first = self.poll().await?;
+
+        while start.elapsed() < ESTIMATE_TIMEOUT {
+            time::sleep(ESTIMATE_POLL_GAP).await;
+            let next = self.poll().await?;
+            if let Some(interval) = update_interval(&first, &next) {
+                return Ok(interval);
+            }
+        }
+
+        Err(Error::NoUpdate(ESTIMATE_TIMEOUT))
+    }
+
+    /// Poll the Awair for its latest air quality data on a fixed cadence.
+    ///
+    /// This is the asynchronous counterpart to [`Awair::stream`](crate::Awair::stream),
+    /// and has the same semantics: request errors are yielded as `Err` items
+    /// without terminating the stream, and samples are scheduled without drifting.
+    ///
+    /// The stream uses [`tokio::time`], and so must be driven from within a
+    /// Tokio runtime with the time driver enabled.
+    ///
+    /// As with the blocking client, intervals shorter than
+    /// [`MIN_STREAM_INTERVAL`](crate::MIN_STREAM_INTERVAL) are raised to it.
+    pub fn stream(&self, interval: Duration) -> impl Stream<Item = Result<AirData, Error>> {
+        let interval = interval.max(MIN_STREAM_INTERVAL);
+        stream::unfold((self.clone(), None), move |(client, ticker)| async move {
+            // The ticker is created lazily, since doing so requires a runtime.
+            let mut ticker = ticker.unwrap_or_else(|| {
+                let mut ticker = time::interval(interval);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                ticker
+            });
+
+            ticker.tick().await;
+            let sample = client.poll().await;
+            Some((sample, (client, Some(ticker))))
+        })
+    }
+
+    /// Like [`AwairAsync::stream`], but ends as soon as `cancel` completes.
+    ///
+    /// Cancellation interrupts any in-progress wait or request, and ends the
+    /// stream without yielding an error. Any future can be used as the
+    /// signal, e.g. `tokio::signal::ctrl_c()` (with its result discarded), or
+    /// a `CancellationToken`'s `cancelled()` future.
+    ///
+    /// This has the same requirements and panics as [`AwairAsync::stream`].
+    pub fn stream_until<C>(
+        &self,
+        interval: Duration,
+        cancel: C,
+    ) -> impl Stream<Item = Result<AirData, Error>>
+    where
+        C: Future<Output = ()>,
+    {
+        self.stream(interval).take_until(cancel)
+    }
+
+    /// Poll the Awair for its latest air quality data on a fixed cadence,
+    /// passing each result to `on_sample` until it returns [`ControlFlow::Break`].
+    ///
+    /// This is the asynchronous counterpart to
+    /// [`Awair::poll_loop`](crate::Awair::poll_loop), and has the same
+    /// requirements and panics as [`AwairAsync::stream`].
+    pub async fn poll_loop<F>(&self, interval: Duration, mut on_sample: F)
+    where
+        F: FnMut(Result<AirData, Error>) -> ControlFlow<()>,
+    {
+        let mut samples = pin!(self.stream(interval));
+        while let Some(sample) = samples.next().await {
+            if on_sample(sample).is_break() {
+                break;
+            }
+        }
+    }
+
+    /// Poll the Awair for its latest air quality data, along with the sample's
+    /// timestamp in the device's configured timezone.
+    ///
+    /// See [`Awair::poll_local`](crate::Awair::poll_local).
+    #[cfg(feature = "chrono-tz")]
+    pub async fn poll_local(&self) -> Result<(AirData, DateTime<chrono_tz::Tz>), Error> {
+        let tz = match cached_timezone(&self.timezone) {
+            Some(tz) => tz,
+            None => {
+                let tz = self.config().await?.timezone()?;
+                cache_timezone(&self.timezone, tz);
+                tz
+            }
+        };
+
+        let data = self.poll().await?;
+        let local = data.timestamp_local(&tz);
+        Ok((data, local))
+    }
+
+    /// Request the Awair's configuration state.
+    ///
+    /// See [`Awair::config`](crate::Awair::config).
+    pub async fn config(&self) -> Result<DeviceConfig, Error> {
+        let config = self.get(&self.config_path).await?;
+        remember_firmware(&config, &self.firmware);
+        Ok(config)
+    }
+
+    /// Like [`AwairAsync::config`], but also return how long the request took.
+    pub async fn config_timed(&self) -> Result<(DeviceConfig, Duration), Error> {
+        let start = Instant::now();
+        self.config().await.map(|config| (config, start.elapsed()))
+    }
+
+    /// Change the Awair's display mode, returning the device's updated configuration.
+    ///
+    /// NOTE: The device may silently ignore modes that it doesn't support, so
+    /// the returned configuration's [`DeviceConfig::display`] may not be `mode`.
+    pub async fn set_display_mode(&self, mode: DisplayMode) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &DisplayUpdate { display: mode })
+            .await
+    }
+
+    /// Check whether the Awair is currently using the given display mode, by
+    /// re-reading its configuration.
+    ///
+    /// This is useful for confirming that a change made with
+    /// [`AwairAsync::set_display_mode`] took effect.
+    pub async fn verify_display_mode(&self, expected: DisplayMode) -> Result<bool, Error> {
+        Ok(self.config().await?.display == expected)
+    }
+
+    /// Change the Awair's LED mode and brightness, returning the device's
+    /// updated configuration.
+    ///
+    /// See [`Awair::set_led`](crate::Awair::set_led) for how `brightness` is
+    /// validated.
+    pub async fn set_led(&self, mode: LedMode, brightness: u8) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &LedUpdate::new(mode, brightness)?)
+            .await
+    }
+
+    /// Enable or disable the Awair's knock-to-wake display feature, returning
+    /// the device's updated configuration.
+    pub async fn set_knocking(&self, enabled: bool) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &KnockingUpdate { knocking: enabled })
+            .await
+    }
+
+    /// Apply the writable settings in the given configuration to the Awair,
+    /// returning the device's updated configuration.
+    ///
+    /// See [`Awair::apply_config`](crate::Awair::apply_config).
+    pub async fn apply_config(&self, config: &DeviceConfig) -> Result<DeviceConfig, Error> {
+        self.put(&self.config_path, &config.to_update_body()?).await
+    }
+
+    /// Capture the Awair's configuration.
+    ///
+    /// See [`Awair::export_config`](crate::Awair::export_config).
+    pub async fn export_config(&self) -> Result<DeviceConfig, Error> {
+        self.config().await
+    }
+
+    /// Apply a configuration captured with [`AwairAsync::export_config`],
+    /// returning the device's updated configuration.
+    ///
+    /// See [`Awair::import_config`](crate::Awair::import_config).
+    pub async fn import_config(&self, config: &DeviceConfig) -> Result<DeviceConfig, Error> {
+        self.apply_config(config).await
+    }
+
+    /// Poll the Awair for its latest air quality data, tolerating malformed fields.
+    ///
+    /// See [`Awair::poll_lenient`](crate::Awair::poll_lenient).
+    pub async fn poll_lenient(&self) -> Result<(AirData, Vec<FieldError>), Error> {
+        Ok(partial::parse(self.poll_raw_json().await?))
+    }
+
+    /// Poll the Awair for its latest air quality data, as untyped JSON.
+    ///
+    /// This is the untyped counterpart to [`Awair::poll`](crate::Awair::poll)
+    /// (not [`Awair::poll_raw`](crate::Awair::poll_raw)), and is useful for
+    /// inspecting fields that [`AirData`] doesn't model, or for debugging
+    /// deserialization failures.
+    pub async fn poll_raw_json(&self) -> Result<serde_json::Value, Error> {
+        self.get(&self.latest_path).await
+    }
+
+    /// Request the Awair's configuration state, as untyped JSON.
+    ///
+    /// This is the untyped counterpart to [`Awair::config`](crate::Awair::config).
+    pub async fn config_json(&self) -> Result<serde_json::Value, Error> {
+        self.get(&self.config_path).await
+    }
+
+    /// Check whether the Awair is reachable and responding successfully,
+    /// without parsing its response.
+    ///
+    /// See [`Awair::ping`](crate::Awair::ping).
+    pub async fn ping(&self) -> Result<bool, Error> {
+        let url = endpoint_url(&self.api_base, &self.latest_path)?;
+
+        match self.checked(&self.latest_path, self.http.get(url)).await {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.without_endpoint(), Error::HttpStatus { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Probe the Awair for the optional endpoints that it supports.
+    ///
+    /// See [`Awair::capabilities`](crate::Awair::capabilities).
+    pub async fn capabilities(&self) -> Result<Capabilities, Error> {
+        Ok(Capabilities {
+            raw: self.probe(RAW_PATH).await?,
+            five_min_avg: self.probe(FIVE_MIN_AVG_PATH).await?,
+            fifteen_min_avg: self.probe(FIFTEEN_MIN_AVG_PATH).await?,
+            settings: self.probe(&self.config_path).await?,
+        })
+    }
+
+    /// Issue a GET request to the given endpoint, deserializing its JSON response.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        check_firmware(path, &self.firmware, &self.min_firmware)?;
+        let url = endpoint_url(&self.api_base, path)?;
+
+        self.send(path, self.http.get(url)).await
+    }
+
+    /// Issue a PUT request with the given JSON body to the given endpoint,
+    /// deserializing its JSON response.
+    async fn put<B: Serialize, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, Error> {
+        let url = endpoint_url(&self.api_base, path)?;
+
+        self.send(path, self.http.put(url, body)).await
+    }
+
+    /// Check whether the given endpoint exists, by issuing a GET request to it.
+    async fn probe(&self, path: &str) -> Result<bool, Error> {
+        let url = endpoint_url(&self.api_base, path)?;
+
+        match self.checked(path, self.http.get(url)).await {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.without_endpoint(), Error::HttpStatus { status: 404, .. }) => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Send the given request to the given endpoint, deserializing its JSON response.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        request: reqwest::Result<Request>,
+    ) -> Result<T, Error> {
+        let response = self.checked(path, request).await?;
+        check_content_type(response.headers()).map_err(|e| e.at(path))?;
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| Error::from(e).at(path))
+    }
+
+    /// Send the given request to the given endpoint, turning an unsuccessful
+    /// response into an error.
+    async fn checked(
+        &self,
+        path: &str,
+        request: reqwest::Result<Request>,
+    ) -> Result<Response, Error> {
+        #[cfg(feature = "tracing")]
+        let start = Instant::now();
+
+        let checked = async {
+            let response = match request {
+                Ok(mut request) => {
+                    if let Some(timeout) = self.timeout {
+                        *request.timeout_mut() = Some(timeout);
+                    }
+
+                    #[cfg(feature = "log")]
+                    log::debug!("{} {}", request.method(), request.url());
+
+                    self.http.execute(request).await
+                }
+                Err(e) => Err(e.into()),
+            };
+
+            let result = match response {
+                Ok(response) => {
+                    #[cfg(feature = "tracing")]
+                    {
+                        let span = tracing::Span::current();
+                        span.record("status", response.status().as_u16());
+                        span.record("latency_ms", start.elapsed().as_millis() as u64);
+                    }
+                    #[cfg(feature = "log")]
+                    log_response_size(path, response.content_length());
+
+                    check_status(response).await
+                }
+                Err(e) => Err(e),
+            };
+
+            #[cfg(feature = "tracing")]
+            if let Err(e) = &result {
+                tracing::debug!(error = %e, "request failed");
+            }
+            #[cfg(feature = "log")]
+            if let Err(e) = &result {
+                log::warn!("request to {path} failed: {e}");
+            }
+
+            result
+        };
+
+        #[cfg(feature = "tracing")]
+        let checked = tracing::Instrument::instrument(
+            checked,
+            tracing::debug_span!(
+                "awair_request",
+                path,
+                status = tracing::field::Empty,
+                latency_ms = tracing::field::Empty,
+            ),
+        );
+
+        checked.await.map_err(|e| e.at(path))
+    }
+}
+
+/// The HTTP client that an [`AwairAsync`] sends its requests through.
+#[derive(Clone, Debug)]
+enum HttpClient {
+    Plain(reqwest::Client),
+    #[cfg(feature = "middleware")]
+    Middleware(reqwest_middleware::ClientWithMiddleware),
+}
+
+impl HttpClient {
+    /// Build a GET request to the given URL.
+    fn get(&self, url: url::Url) -> reqwest::Result<Request> {
+        match self {
+            HttpClient::Plain(client) => client.get(url).build(),
+            #[cfg(feature = "middleware")]
+            HttpClient::Middleware(client) => client.get(url).build(),
+        }
+    }
+
+    /// Build a PUT request to the given URL, with the given JSON body.
+    fn put<B: Serialize>(&self, url: url::Url, body: &B) -> reqwest::Result<Request> {
+        match self {
+            HttpClient::Plain(client) => client.put(url).json(body).build(),
+            #[cfg(feature = "middleware")]
+            HttpClient::Middleware(client) => client.put(url).json(body).build(),
+        }
+    }
+
+    /// Send the given request, running it through any middleware.
+    async fn execute(&self, request: Request) -> Result<Response, Error> {
+        match self {
+            HttpClient::Plain(client) => Ok(client.execute(request).await?),
+            #[cfg(feature = "middleware")]
+            HttpClient::Middleware(client) => Ok(client.execute(request).await?),
+        }
+    }
+}
+
+/// Turn an unsuccessful response into an [`Error::HttpStatus`].
+async fn check_status(response: Response) -> Result<Response, Error> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(status_error(
+            status,
+            response.text().await.unwrap_or_default(),
+        ));
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::testing::{MockAwair, MockResponse, SAMPLE_AIR_DATA, SAMPLE_CONFIG};
+
+    #[tokio::test]
+    async fn test_stream_spacing() {
+        let mock = MockAwair::start().unwrap();
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+
+        let start = Instant::now();
+        let samples = client
+            .stream(Duration::from_millis(200))
+            .take(3)
+            .collect::<Vec<_>>()
+            .await;
+        let elapsed = start.elapsed();
+
+        assert_eq!(samples.len(), 3);
+        assert!(samples.iter().all(Result::is_ok));
+        assert!(elapsed >= Duration::from_millis(400));
+        assert!(elapsed < Duration::from_millis(2000));
+    }
+
+    #[tokio::test]
+    async fn test_stream_zero_interval() {
+        let mock = MockAwair::start().unwrap();
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(client.stream(Duration::ZERO).take(3).count().await, 3);
+        assert!(start.elapsed() >= MIN_STREAM_INTERVAL * 2);
+    }
+
+    #[tokio::test]
+    async fn test_poll_timed() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_delay(Duration::from_millis(100));
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+
+        let (data, latency) = client.poll_timed().await.unwrap();
+        assert_eq!(data.score, 90);
+        assert!(latency >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_stream_until() {
+        let mock = MockAwair::start().unwrap();
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+
+        // The first sample arrives immediately, and the next wouldn't for ages.
+        let start = Instant::now();
+        let samples = client
+            .stream_until(
+                Duration::from_secs(30),
+                tokio::time::sleep(Duration::from_millis(300)),
+            )
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(samples.len(), 1);
+        assert!(samples[0].is_ok());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_estimate_update_interval() {
+        let mock = MockAwair::start().unwrap();
+        let mut next: AirData = serde_json::from_str(crate::testing::SAMPLE_AIR_DATA).unwrap();
+        next.timestamp += chrono::Duration::seconds(10);
+        mock.set_response(
+            "/air-data/latest",
+            crate::testing::MockResponse::json(&serde_json::to_value(&next).unwrap()),
+        );
+        mock.queue_response(crate::testing::MockResponse::new(
+            200,
+            crate::testing::SAMPLE_AIR_DATA,
+        ));
+
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+        let interval = client.estimate_update_interval().await.unwrap();
+        assert_eq!(interval, Duration::from_secs(10));
+    }
+
+    /// A middleware that tags each request with a header, or fails it if it's
+    /// to the configuration endpoint.
+    #[cfg(feature = "middleware")]
+    fn tag_request<'a>(
+        mut request: Request,
+        extensions: &'a mut http::Extensions,
+        next: reqwest_middleware::Next<'a>,
+    ) -> futures_util::future::BoxFuture<'a, reqwest_middleware::Result<Response>> {
+        if request.url().path() == CONFIG_PATH {
+            return Box::pin(async { Err(reqwest_middleware::Error::middleware(std::fmt::Error)) });
+        }
+
+        request.headers_mut().insert(
+            "x-middleware",
+            reqwest::header::HeaderValue::from_static("tagged"),
+        );
+        Box::pin(next.run(request, extensions))
+    }
+
+    #[cfg(feature = "middleware")]
+    #[tokio::test]
+    async fn test_middleware_client() {
+        let mock = MockAwair::start().unwrap();
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(tag_request)
+            .build();
+        let client = AwairAsync::with_middleware_client(&mock.api_base(), client).unwrap();
+
+        assert_eq!(client.poll().await.unwrap().score, 90);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("x-middleware"), Some("tagged"));
+
+        // Middleware failures surface as errors, without contacting the device.
+        let err = client.config().await.unwrap_err();
+        assert_eq!(err.endpoint(), Some(CONFIG_PATH));
+        assert!(matches!(err.without_endpoint(), Error::Middleware(_)));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_poll_labeled() {
+        let mock = MockAwair::start().unwrap();
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+
+        for _ in 0..3 {
+            let labeled = client.poll_labeled().await.unwrap();
+            assert_eq!(labeled.device_id, "awair-element_1234");
+        }
+
+        let config_requests = mock
+            .requests()
+            .iter()
+            .filter(|request| request.path == "/settings/config/data")
+            .count();
+        assert_eq!(config_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_min_firmware() {
+        let mock = MockAwair::start().unwrap();
+        let client = AwairAsync::new(&mock.api_base())
+            .unwrap()
+            // The mock runs 1.2.4.
+            .min_firmware(FIVE_MIN_AVG_PATH, semver::Version::new(2, 0, 0));
+
+        // Until the firmware version is known, requests are made as usual.
+        assert!(client.five_minute_average().await.is_ok());
+
+        client.config().await.unwrap();
+        match client
+            .five_minute_average()
+            .await
+            .unwrap_err()
+            .without_endpoint()
+        {
+            Error::UnsupportedByFirmware { endpoint, required } => {
+                assert_eq!(endpoint, FIVE_MIN_AVG_PATH);
+                assert_eq!(*required, semver::Version::new(2, 0, 0));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert_eq!(
+            mock.requests()
+                .iter()
+                .filter(|request| request.path == FIVE_MIN_AVG_PATH)
+                .count(),
+            1
+        );
+
+        // New enough firmware proceeds, including under the default table.
+        assert!(client.fifteen_minute_average().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_delay(Duration::from_millis(500));
+        let client = AwairAsync::new(&mock.api_base())
+            .unwrap()
+            .timeout(Duration::from_millis(100));
+
+        let start = Instant::now();
+        let err = client.poll().await.unwrap_err();
+        assert!(matches!(err.without_endpoint(), Error::Timeout(Some(_))));
+        assert!(start.elapsed() < Duration::from_millis(500));
+
+        // Lengthening the timeout lets the same request through.
+        let client = client.timeout(Duration::from_secs(2));
+        assert!(client.poll().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_custom_paths() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(
+            "/v2/air",
+            MockResponse::json(&serde_json::from_str(SAMPLE_AIR_DATA).unwrap()),
+        );
+        mock.set_response(
+            "/v2/config",
+            MockResponse::json(&serde_json::from_str(SAMPLE_CONFIG).unwrap()),
+        );
+
+        let client = AwairAsync::new(&mock.api_base())
+            .unwrap()
+            .latest_path("/v2/air")
+            .config_path("v2/config");
+        assert_eq!(client.poll().await.unwrap().score, 90);
+        assert_eq!(
+            client.config().await.unwrap().device_id,
+            "awair-element_1234"
+        );
+
+        let paths = mock
+            .requests()
+            .into_iter()
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["/v2/air", "/v2/config"]);
+    }
+
+    #[tokio::test]
+    async fn test_http_status_error() {
+        let mock = MockAwair::start().unwrap();
+        mock.queue_response(MockResponse::new(503, "warming up"));
+        let client = AwairAsync::new(&mock.api_base()).unwrap();
+
+        let err = client.poll().await.unwrap_err();
+        assert_eq!(err.endpoint(), Some(LATEST_PATH));
+        match err.without_endpoint() {
+            Error::HttpStatus { status, body } => {
+                assert_eq!(*status, 503);
+                assert_eq!(body, "warming up");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Partial parsing of air quality samples, for samples with malformed fields.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::AirData;
+
+/// A field of an air quality sample that couldn't be parsed, as returned by
+/// [`Awair::poll_lenient`](crate::Awair::poll_lenient).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldError {
+    /// The field's Local API name, e.g. `co2`.
+    pub field: String,
+    /// A description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// The sample used to fill in malformed fields: the Unix epoch, zeros for
+/// every reading, and `None` for model-dependent readings.
+fn placeholder() -> AirData {
+    AirData {
+        timestamp: Default::default(),
+        score: 0,
+        dew_point: 0.0,
+        temperature: 0.0,
+        humidity: 0.0,
+        absolute_humidity: 0.0,
+        co2: 0,
+        estimated_co2: None,
+        estimated_co2_baseline: None,
+        voc: 0,
+        voc_baseline: 0,
+        voc_h2_raw: 0,
+        voc_ethanol_raw: 0,
+        pm25: None,
+        estimated_pm10: None,
+        lux: None,
+        spl_a: None,
+        score_breakdown: None,
+        extra: HashMap::new(),
+    }
+}
+
+/// Parse a sample from untyped JSON, replacing any malformed or missing
+/// fields with those of the [`placeholder`] and reporting them.
+pub(crate) fn parse(raw: Value) -> (AirData, Vec<FieldError>) {
+    let placeholder = placeholder();
+    let defaults = match serde_json::to_value(&placeholder) {
+        Ok(Value::Object(defaults)) => defaults,
+        _ => Map::new(),
+    };
+    let mut fields = match raw {
+        Value::Object(fields) => fields,
+        _ => Map::new(),
+    };
+
+    let mut errors = vec![];
+    for (field, default) in &defaults {
+        // NOTE: Each field is checked in isolation, against otherwise
+        // placeholder fields, so that we know exactly which ones are bad.
+        let message = match fields.get(field) {
+            None if default.is_null() => continue,
+            None => "missing field".into(),
+            Some(value) => {
+                let mut probe = defaults.clone();
+                probe.insert(field.clone(), value.clone());
+                match serde_json::from_value::<AirData>(Value::Object(probe)) {
+                    Ok(_) => continue,
+                    Err(e) => e.to_string(),
+                }
+            }
+        };
+
+        fields.insert(field.clone(), default.clone());
+        errors.push(FieldError {
+            field: field.clone(),
+            message,
+        });
+    }
+
+    // NOTE: Every field is now individually valid, so this can't fail.
+    let data = serde_json::from_value(Value::Object(fields)).unwrap_or(placeholder);
+
+    (data, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockAwair, MockResponse, SAMPLE_AIR_DATA};
+    use crate::Awair;
+
+    #[test]
+    fn test_poll_lenient() {
+        let mut payload: Value = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        payload["co2"] = "lots".into();
+        payload["pm25"] = serde_json::json!([3]);
+        payload.as_object_mut().unwrap().remove("humid");
+
+        let mock = MockAwair::start().unwrap();
+        mock.queue_response(MockResponse::json(&payload));
+        let (data, errors) = Awair::new(&mock.api_base())
+            .unwrap()
+            .poll_lenient()
+            .unwrap();
+
+        // The bad fields are defaulted, and the rest are kept.
+        assert_eq!(data.co2, 0);
+        assert_eq!(data.pm25, None);
+        assert_eq!(data.humidity, 0.0);
+        assert_eq!(data.voc, 210);
+        assert_eq!(data.temperature, 21.4);
+
+        let mut fields = errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>();
+        fields.sort_unstable();
+        assert_eq!(fields, ["co2", "humid", "pm25"]);
+
+        let missing = errors.iter().find(|error| error.field == "humid").unwrap();
+        assert_eq!(missing.to_string(), "humid: missing field");
+
+        // Transport-level failures are still errors.
+        mock.queue_response(MockResponse::new(500, ""));
+        assert!(Awair::new(&mock.api_base())
+            .unwrap()
+            .poll_lenient()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_clean() {
+        let (data, errors) = parse(serde_json::from_str(SAMPLE_AIR_DATA).unwrap());
+        assert!(errors.is_empty());
+        assert_eq!(data, serde_json::from_str(SAMPLE_AIR_DATA).unwrap());
+    }
+}
@@ -0,0 +1,183 @@
+//! Prometheus text-format exposition for [`AirData`].
+
+use std::fmt::Write;
+
+use crate::AirData;
+
+/// Escape a label value for the Prometheus text format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl AirData {
+    /// Returns this sample in the Prometheus text exposition format.
+    ///
+    /// Each sensor is exposed as a gauge (with `# HELP` and `# TYPE` headers),
+    /// and each metric line carries the given labels. Label values are escaped
+    /// as needed, but label names are used as-is and must be valid Prometheus
+    /// label names. Model-dependent sensors are omitted when the device doesn't
+    /// report them.
+    ///
+    /// The exposed metrics are:
+    ///
+    /// * `awair_score`
+    /// * `awair_dew_point_celsius`
+    /// * `awair_temperature_celsius`
+    /// * `awair_relative_humidity_percent`
+    /// * `awair_absolute_humidity`
+    /// * `awair_co2_ppm`
+    /// * `awair_estimated_co2_ppm`
+    /// * `awair_estimated_co2_baseline`
+    /// * `awair_voc_ppb`
+    /// * `awair_voc_baseline`
+    /// * `awair_voc_h2_raw`
+    /// * `awair_voc_ethanol_raw`
+    /// * `awair_pm25_micrograms_per_cubic_meter`
+    /// * `awair_pm10_micrograms_per_cubic_meter`
+    /// * `awair_illuminance_lux`
+    /// * `awair_sound_pressure_dba`
+    pub fn to_prometheus(&self, labels: &[(&str, &str)]) -> String {
+        let metrics: [(&str, &str, Option<String>); 16] = [
+            (
+                "awair_score",
+                "The Awair Score, from 0-100.",
+                Some(self.score.to_string()),
+            ),
+            (
+                "awair_dew_point_celsius",
+                "The dew point, in degrees Celsius.",
+                Some(self.dew_point.to_string()),
+            ),
+            (
+                "awair_temperature_celsius",
+                "The dry bulb temperature, in degrees Celsius.",
+                Some(self.temperature.to_string()),
+            ),
+            (
+                "awair_relative_humidity_percent",
+                "The relative humidity, as a percent.",
+                Some(self.humidity.to_string()),
+            ),
+            (
+                "awair_absolute_humidity",
+                "The absolute humidity.",
+                Some(self.absolute_humidity.to_string()),
+            ),
+            (
+                "awair_co2_ppm",
+                "The CO2 reading, in parts per million.",
+                Some(self.co2.to_string()),
+            ),
+            (
+                "awair_estimated_co2_ppm",
+                "The VOC sensor's estimated CO2 reading, in parts per million.",
+                self.estimated_co2.map(|value| value.to_string()),
+            ),
+            (
+                "awair_estimated_co2_baseline",
+                "The VOC sensor's CO2 baseline (unitless).",
+                self.estimated_co2_baseline.map(|value| value.to_string()),
+            ),
+            (
+                "awair_voc_ppb",
+                "The TVOC reading, in parts per billion.",
+                Some(self.voc.to_string()),
+            ),
+            (
+                "awair_voc_baseline",
+                "The TVOC sensor's VOC baseline (unitless).",
+                Some(self.voc_baseline.to_string()),
+            ),
+            (
+                "awair_voc_h2_raw",
+                "The TVOC sensor's H2 (hydrogen gas) reading (unitless).",
+                Some(self.voc_h2_raw.to_string()),
+            ),
+            (
+                "awair_voc_ethanol_raw",
+                "The TVOC sensor's ethanol gas reading (unitless).",
+                Some(self.voc_ethanol_raw.to_string()),
+            ),
+            (
+                "awair_pm25_micrograms_per_cubic_meter",
+                "The PM2.5 reading, in micrograms per cubic meter.",
+                self.pm25.map(|value| value.to_string()),
+            ),
+            (
+                "awair_pm10_micrograms_per_cubic_meter",
+                "The estimated PM10 reading, in micrograms per cubic meter.",
+                self.estimated_pm10.map(|value| value.to_string()),
+            ),
+            (
+                "awair_illuminance_lux",
+                "The illuminance, in lux.",
+                self.lux.map(|value| value.to_string()),
+            ),
+            (
+                "awair_sound_pressure_dba",
+                "The A-weighted sound pressure level, in decibels.",
+                self.spl_a.map(|value| value.to_string()),
+            ),
+        ];
+
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            let labels = labels
+                .iter()
+                .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{{{labels}}}")
+        };
+
+        let mut exposition = String::new();
+        for (name, help, value) in metrics {
+            let Some(value) = value else {
+                continue;
+            };
+
+            // Writing to a String is infallible.
+            let _ = writeln!(exposition, "# HELP {name} {help}");
+            let _ = writeln!(exposition, "# TYPE {name} gauge");
+            let _ = writeln!(exposition, "{name}{labels} {value}");
+        }
+
+        exposition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    #[test]
+    fn test_to_prometheus() {
+        let data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        let exposition = data.to_prometheus(&[("room", "office"), ("note", "a \"b\"\\c\nd")]);
+
+        assert!(exposition.contains(
+            "# HELP awair_co2_ppm The CO2 reading, in parts per million.\n\
+             # TYPE awair_co2_ppm gauge\n\
+             awair_co2_ppm{room=\"office\",note=\"a \\\"b\\\"\\\\c\\nd\"} 612\n"
+        ));
+        assert!(exposition.contains(
+            "awair_temperature_celsius{room=\"office\",note=\"a \\\"b\\\"\\\\c\\nd\"} 21.4\n"
+        ));
+
+        // The sample doesn't report lux or sound pressure.
+        assert!(!exposition.contains("awair_illuminance_lux"));
+        assert!(!exposition.contains("awair_sound_pressure_dba"));
+        assert_eq!(exposition.lines().count(), 14 * 3);
+    }
+
+    #[test]
+    fn test_to_prometheus_no_labels() {
+        let data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        assert!(data.to_prometheus(&[]).contains("\nawair_score 90\n"));
+    }
+}
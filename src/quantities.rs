@@ -0,0 +1,87 @@
+//! Dimensional quantities for [`AirData`], via `uom`.
+//!
+//! The sensors map onto `uom` quantities as follows:
+//!
+//! | Sensor                 | Quantity                      |
+//! | ---------------------- | ----------------------------- |
+//! | Temperature, dew point | [`ThermodynamicTemperature`]  |
+//! | Relative humidity      | [`Ratio`] (percent)           |
+//! | CO2                    | [`Ratio`] (parts per million) |
+//! | TVOC                   | [`Ratio`] (parts per billion) |
+//! | PM2.5, PM10            | [`MassConcentration`]         |
+
+use uom::si::f32::{MassConcentration, Ratio, ThermodynamicTemperature};
+use uom::si::mass_concentration::microgram_per_cubic_meter;
+use uom::si::ratio::{part_per_billion, part_per_million, percent};
+use uom::si::thermodynamic_temperature::degree_celsius;
+
+use crate::AirData;
+
+impl AirData {
+    /// The dry bulb temperature, as a `uom` quantity.
+    pub fn temperature_quantity(&self) -> ThermodynamicTemperature {
+        ThermodynamicTemperature::new::<degree_celsius>(self.temperature)
+    }
+
+    /// The dew point, as a `uom` quantity.
+    pub fn dew_point_quantity(&self) -> ThermodynamicTemperature {
+        ThermodynamicTemperature::new::<degree_celsius>(self.dew_point)
+    }
+
+    /// The relative humidity, as a `uom` quantity.
+    pub fn humidity_quantity(&self) -> Ratio {
+        Ratio::new::<percent>(self.humidity)
+    }
+
+    /// The CO2 reading, as a `uom` quantity.
+    pub fn co2_quantity(&self) -> Ratio {
+        Ratio::new::<part_per_million>(self.co2 as f32)
+    }
+
+    /// The TVOC reading, as a `uom` quantity.
+    pub fn voc_quantity(&self) -> Ratio {
+        Ratio::new::<part_per_billion>(self.voc as f32)
+    }
+
+    /// The PM2.5 reading, as a `uom` quantity, if the device reports one.
+    pub fn pm25_quantity(&self) -> Option<MassConcentration> {
+        self.pm25
+            .map(|pm25| MassConcentration::new::<microgram_per_cubic_meter>(pm25 as f32))
+    }
+
+    /// The estimated PM10 reading, as a `uom` quantity, if the device reports one.
+    pub fn pm10_quantity(&self) -> Option<MassConcentration> {
+        self.estimated_pm10
+            .map(|pm10| MassConcentration::new::<microgram_per_cubic_meter>(pm10 as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::ratio::ratio;
+    use uom::si::thermodynamic_temperature::{degree_fahrenheit, kelvin};
+
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    #[test]
+    fn test_quantities() {
+        let data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+
+        let temperature = data.temperature_quantity();
+        assert!((temperature.get::<degree_celsius>() - data.temperature).abs() < 1e-3);
+        assert!((temperature.get::<kelvin>() - 294.55).abs() < 1e-3);
+        assert!(
+            (temperature.get::<degree_fahrenheit>() - data.temperature_fahrenheit()).abs() < 1e-3
+        );
+
+        assert!((data.humidity_quantity().get::<ratio>() - 0.421).abs() < 1e-6);
+        assert!((data.co2_quantity().get::<ratio>() - 612e-6).abs() < 1e-9);
+        assert!((data.voc_quantity().get::<part_per_billion>() - 210.0).abs() < 1e-3);
+        assert_eq!(
+            data.pm25_quantity()
+                .map(|pm25| pm25.get::<microgram_per_cubic_meter>().round()),
+            Some(3.0)
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Model-agnostic views of an [`AirData`] sample's sensor readings.
+
+use chrono::{DateTime, Utc};
+
+use crate::{widen, AirData};
+
+/// A common interface to environmental sensor readings, for treating Awair
+/// devices and other sensors uniformly.
+///
+/// Readings that a sensor doesn't have return `None`, which is the default
+/// for each of them.
+pub trait SensorReading {
+    /// The time that the readings were taken.
+    fn timestamp(&self) -> DateTime<Utc>;
+
+    /// The temperature, in degrees Celsius.
+    fn temperature_celsius(&self) -> Option<f32> {
+        None
+    }
+
+    /// The relative humidity, as a percent.
+    fn relative_humidity(&self) -> Option<f32> {
+        None
+    }
+
+    /// The CO2 concentration, in parts per million.
+    fn co2_ppm(&self) -> Option<u32> {
+        None
+    }
+
+    /// The TVOC concentration, in parts per billion.
+    fn voc_ppb(&self) -> Option<u32> {
+        None
+    }
+
+    /// The PM2.5 concentration, in micrograms per cubic meter.
+    fn pm25_micrograms_per_cubic_meter(&self) -> Option<u32> {
+        None
+    }
+}
+
+impl SensorReading for AirData {
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn temperature_celsius(&self) -> Option<f32> {
+        Some(self.temperature)
+    }
+
+    fn relative_humidity(&self) -> Option<f32> {
+        Some(self.humidity)
+    }
+
+    fn co2_ppm(&self) -> Option<u32> {
+        Some(self.co2)
+    }
+
+    fn voc_ppb(&self) -> Option<u32> {
+        Some(self.voc)
+    }
+
+    fn pm25_micrograms_per_cubic_meter(&self) -> Option<u32> {
+        self.pm25
+    }
+}
+
+/// A single sensor reading, as returned by [`AirData::readings`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Reading {
+    /// The reading's Local API field name, e.g. `temp` or `co2`.
+    pub name: &'static str,
+    /// The reading's value.
+    pub value: f64,
+    /// The reading's unit, e.g. `°C` or `ppm`. Unitless readings have an
+    /// empty unit.
+    pub unit: &'static str,
+}
+
+impl AirData {
+    /// Returns each of this sample's sensor readings, in Local API field order.
+    ///
+    /// Model-dependent readings are omitted when the device doesn't report them.
+    pub fn readings(&self) -> Vec<Reading> {
+        [
+            ("score", Some(self.score.into()), ""),
+            ("dew_point", Some(widen(self.dew_point)), "°C"),
+            ("temp", Some(widen(self.temperature)), "°C"),
+            ("humid", Some(widen(self.humidity)), "%"),
+            ("abs_humid", Some(widen(self.absolute_humidity)), "g/m³"),
+            ("co2", Some(self.co2.into()), "ppm"),
+            ("co2_est", self.estimated_co2.map(Into::into), "ppm"),
+            (
+                "co2_est_baseline",
+                self.estimated_co2_baseline.map(Into::into),
+                "",
+            ),
+            ("voc", Some(self.voc.into()), "ppb"),
+            ("voc_baseline", Some(self.voc_baseline.into()), ""),
+            ("voc_h2_raw", Some(self.voc_h2_raw.into()), ""),
+            ("voc_ethanol_raw", Some(self.voc_ethanol_raw.into()), ""),
+            ("pm25", self.pm25.map(Into::into), "µg/m³"),
+            ("pm10_est", self.estimated_pm10.map(Into::into), "µg/m³"),
+            ("lux", self.lux.map(widen), "lx"),
+            ("spl_a", self.spl_a.map(widen), "dBA"),
+        ]
+        .into_iter()
+        .filter_map(|(name, value, unit)| {
+            Some(Reading {
+                name,
+                value: value?,
+                unit,
+            })
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    #[test]
+    fn test_readings() {
+        let mut data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        let readings = data.readings();
+
+        // The mock's sample has every reading but the Omni's light and sound.
+        assert_eq!(
+            readings
+                .iter()
+                .map(|reading| reading.name)
+                .collect::<Vec<_>>(),
+            [
+                "score",
+                "dew_point",
+                "temp",
+                "humid",
+                "abs_humid",
+                "co2",
+                "co2_est",
+                "co2_est_baseline",
+                "voc",
+                "voc_baseline",
+                "voc_h2_raw",
+                "voc_ethanol_raw",
+                "pm25",
+                "pm10_est",
+            ]
+        );
+        assert_eq!(
+            readings[2],
+            Reading {
+                name: "temp",
+                value: 21.4,
+                unit: "°C"
+            }
+        );
+
+        data.pm25 = None;
+        data.lux = Some(120.5);
+        let readings = data.readings();
+        assert_eq!(readings.len(), 14);
+        assert!(readings.iter().all(|reading| reading.name != "pm25"));
+        assert_eq!(readings.last().map(|reading| reading.unit), Some("lx"));
+    }
+
+    /// A sensor that only measures temperature.
+    struct Thermometer {
+        timestamp: DateTime<Utc>,
+        celsius: f32,
+    }
+
+    impl SensorReading for Thermometer {
+        fn timestamp(&self) -> DateTime<Utc> {
+            self.timestamp
+        }
+
+        fn temperature_celsius(&self) -> Option<f32> {
+            Some(self.celsius)
+        }
+    }
+
+    #[test]
+    fn test_sensor_reading_objects() {
+        let data: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        let thermometer = Thermometer {
+            timestamp: data.timestamp,
+            celsius: 19.5,
+        };
+
+        let sensors: Vec<&dyn SensorReading> = vec![&data, &thermometer];
+        assert_eq!(
+            sensors
+                .iter()
+                .map(|sensor| sensor.temperature_celsius())
+                .collect::<Vec<_>>(),
+            [Some(21.4), Some(19.5)]
+        );
+        assert_eq!(
+            sensors
+                .iter()
+                .map(|sensor| sensor.co2_ppm())
+                .collect::<Vec<_>>(),
+            [Some(612), None]
+        );
+        assert_eq!(sensors[1].relative_humidity(), None);
+        assert_eq!(sensors[1].timestamp(), data.timestamp);
+    }
+}
@@ -0,0 +1,127 @@
+//! SQLite persistence for [`AirData`].
+
+use std::path::Path;
+
+use chrono::SecondsFormat;
+use rusqlite::{params, Connection};
+
+use crate::{widen, AirData, Error};
+
+/// The schema for the table of samples, keyed by timestamp.
+///
+/// Columns use the same names as the Local API's fields, and timestamps are
+/// stored as RFC 3339 strings in UTC, so that they sort chronologically.
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS air_data (
+    timestamp TEXT PRIMARY KEY NOT NULL,
+    score INTEGER NOT NULL,
+    dew_point REAL NOT NULL,
+    temp REAL NOT NULL,
+    humid REAL NOT NULL,
+    abs_humid REAL NOT NULL,
+    co2 INTEGER NOT NULL,
+    co2_est INTEGER,
+    co2_est_baseline INTEGER,
+    voc INTEGER NOT NULL,
+    voc_baseline INTEGER NOT NULL,
+    voc_h2_raw INTEGER NOT NULL,
+    voc_ethanol_raw INTEGER NOT NULL,
+    pm25 INTEGER,
+    pm10_est INTEGER,
+    lux REAL,
+    spl_a REAL
+)";
+
+/// Insert a sample, replacing any existing sample with the same timestamp.
+const UPSERT: &str = "INSERT OR REPLACE INTO air_data (
+    timestamp, score, dew_point, temp, humid, abs_humid, co2, co2_est,
+    co2_est_baseline, voc, voc_baseline, voc_h2_raw, voc_ethanol_raw, pm25,
+    pm10_est, lux, spl_a
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)";
+
+/// A sink that persists samples to an `air_data` table in a SQLite database.
+#[derive(Debug)]
+pub struct SqliteSink {
+    conn: Connection,
+}
+
+impl SqliteSink {
+    /// Open (or create) the SQLite database at `path`, creating the
+    /// `air_data` table if it doesn't already exist.
+    ///
+    /// A `path` of `:memory:` opens a temporary in-memory database.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let conn = Connection::open(path)?;
+        conn.execute(SCHEMA, [])?;
+
+        Ok(Self { conn })
+    }
+
+    /// Insert a sample, replacing any existing sample with the same timestamp.
+    pub fn insert(&self, sample: &AirData) -> Result<(), Error> {
+        self.conn.prepare_cached(UPSERT)?.execute(params![
+            sample
+                .timestamp
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            sample.score,
+            widen(sample.dew_point),
+            widen(sample.temperature),
+            widen(sample.humidity),
+            widen(sample.absolute_humidity),
+            sample.co2,
+            sample.estimated_co2,
+            sample.estimated_co2_baseline,
+            sample.voc,
+            sample.voc_baseline,
+            sample.voc_h2_raw,
+            sample.voc_ethanol_raw,
+            sample.pm25,
+            sample.estimated_pm10,
+            sample.lux.map(widen),
+            sample.spl_a.map(widen),
+        ])?;
+
+        Ok(())
+    }
+
+    /// Returns the underlying database connection, e.g. for querying samples.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    #[test]
+    fn test_insert() {
+        let sink = SqliteSink::new(":memory:").unwrap();
+        let mut sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+
+        sink.insert(&sample).unwrap();
+        let (timestamp, temperature, co2, lux): (String, f64, u32, Option<f64>) = sink
+            .connection()
+            .query_row(
+                "SELECT timestamp, temp, co2, lux FROM air_data",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(timestamp, "2024-01-01T00:00:00.000Z");
+        assert_eq!(temperature, 21.4);
+        assert_eq!(co2, 612);
+        assert_eq!(lux, None);
+
+        // Re-inserting a sample with the same timestamp replaces it.
+        sample.co2 = 700;
+        sink.insert(&sample).unwrap();
+        let (count, co2): (u32, u32) = sink
+            .connection()
+            .query_row("SELECT COUNT(*), MAX(co2) FROM air_data", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((count, co2), (1, 700));
+    }
+}
@@ -0,0 +1,932 @@
+//! Aggregations and statistics over collections of [`AirData`] samples.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::{AirData, Reading};
+
+/// The mean of a field over some samples.
+fn mean_f32(samples: &[&AirData], field: impl Fn(&AirData) -> f32) -> f32 {
+    samples.iter().map(|sample| field(sample)).sum::<f32>() / samples.len() as f32
+}
+
+/// The mean of a model-dependent field, over only the samples that report
+/// it. Returns `None` if no sample reports it.
+fn mean_optional_f32(samples: &[&AirData], field: impl Fn(&AirData) -> Option<f32>) -> Option<f32> {
+    let values = samples
+        .iter()
+        .filter_map(|sample| field(sample))
+        .collect::<Vec<_>>();
+
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// The mean of an integer field over some samples, rounded to the nearest integer.
+fn mean_u32(samples: &[&AirData], field: impl Fn(&AirData) -> u32) -> u32 {
+    let sum = samples
+        .iter()
+        .map(|sample| u64::from(field(sample)))
+        .sum::<u64>();
+
+    (sum as f64 / samples.len() as f64).round() as u32
+}
+
+/// The mean of a model-dependent integer field, over only the samples that
+/// report it. Returns `None` if no sample reports it.
+fn mean_optional_u32(samples: &[&AirData], field: impl Fn(&AirData) -> Option<u32>) -> Option<u32> {
+    let values = samples
+        .iter()
+        .filter_map(|sample| field(sample))
+        .collect::<Vec<_>>();
+
+    if values.is_empty() {
+        return None;
+    }
+
+    let sum = values.iter().copied().map(u64::from).sum::<u64>();
+    Some((sum as f64 / values.len() as f64).round() as u32)
+}
+
+/// Compute the field-wise mean of some samples, taking the newest sample's
+/// timestamp, score breakdown, and unmodeled fields. Returns `None` if there
+/// are no samples.
+pub(crate) fn mean(samples: &[&AirData]) -> Option<AirData> {
+    let newest = samples.iter().max_by_key(|sample| sample.timestamp)?;
+
+    Some(AirData {
+        timestamp: newest.timestamp,
+        score: mean_u32(samples, |s| s.score.into()) as u8,
+        dew_point: mean_f32(samples, |s| s.dew_point),
+        temperature: mean_f32(samples, |s| s.temperature),
+        humidity: mean_f32(samples, |s| s.humidity),
+        absolute_humidity: mean_f32(samples, |s| s.absolute_humidity),
+        co2: mean_u32(samples, |s| s.co2),
+        estimated_co2: mean_optional_u32(samples, |s| s.estimated_co2),
+        estimated_co2_baseline: mean_optional_u32(samples, |s| s.estimated_co2_baseline),
+        voc: mean_u32(samples, |s| s.voc),
+        voc_baseline: mean_u32(samples, |s| s.voc_baseline),
+        voc_h2_raw: mean_u32(samples, |s| s.voc_h2_raw),
+        voc_ethanol_raw: mean_u32(samples, |s| s.voc_ethanol_raw),
+        pm25: mean_optional_u32(samples, |s| s.pm25),
+        estimated_pm10: mean_optional_u32(samples, |s| s.estimated_pm10),
+        lux: mean_optional_f32(samples, |s| s.lux),
+        spl_a: mean_optional_f32(samples, |s| s.spl_a),
+        score_breakdown: newest.score_breakdown,
+        extra: newest.extra.clone(),
+    })
+}
+
+/// Compute the field-wise mean of samples taken at about the same time, e.g.
+/// by several devices in the same room (see [`MultiAwair::poll_all`]).
+///
+/// The result takes the newest sample's timestamp, score breakdown, and
+/// unmodeled fields. Integer fields are rounded to the nearest integer, and
+/// model-dependent fields are averaged over only the samples that report
+/// them. Returns `None` if there are no samples.
+///
+/// [`MultiAwair::poll_all`]: crate::MultiAwair::poll_all
+pub fn average_across(samples: &[AirData]) -> Option<AirData> {
+    mean(&samples.iter().collect::<Vec<_>>())
+}
+
+/// Sort samples from oldest to newest.
+///
+/// The sort is stable, so samples that share a timestamp keep their relative order.
+pub fn sort_by_timestamp(samples: &mut [AirData]) {
+    samples.sort_by_key(|sample| sample.timestamp);
+}
+
+/// Remove consecutive samples that share a timestamp, keeping the last of each run.
+///
+/// As with [`Vec::dedup`], only consecutive duplicates are removed, so the
+/// samples should usually be sorted first with [`sort_by_timestamp`].
+pub fn dedup_by_timestamp(samples: &mut Vec<AirData>) {
+    // NOTE: `dedup_by_key` keeps the first of each run, so we run it backwards.
+    samples.reverse();
+    samples.dedup_by_key(|sample| sample.timestamp);
+    samples.reverse();
+}
+
+/// A rolling average over a fixed-size window of the most recent samples.
+#[derive(Clone, Debug)]
+pub struct AirDataAverager {
+    window: usize,
+    samples: VecDeque<AirData>,
+}
+
+impl AirDataAverager {
+    /// Create a new averager over the given number of samples.
+    ///
+    /// A window of zero is treated as a window of one.
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Add a sample to the window, dropping the oldest sample if the window is full.
+    pub fn push(&mut self, sample: AirData) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    /// Compute the average of the samples in the window, or `None` if the
+    /// window is empty.
+    ///
+    /// Each reading is averaged over the window (integer readings are rounded
+    /// to the nearest integer), and model-dependent readings are averaged over
+    /// only the samples that report them. The result takes its timestamp and
+    /// unmodeled fields from the newest sample in the window.
+    pub fn average(&self) -> Option<AirData> {
+        mean(&self.samples.iter().collect::<Vec<_>>())
+    }
+}
+
+/// The per-field differences between two samples, as returned by [`AirData::diff`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AirDataDelta {
+    /// The difference between the samples' timestamps.
+    pub timestamp: chrono::Duration,
+    /// The difference in Awair Score.
+    pub score: i16,
+    /// The difference in dew point, in degrees Celsius.
+    pub dew_point: f32,
+    /// The difference in dry bulb temperature, in degrees Celsius.
+    pub temperature: f32,
+    /// The difference in relative humidity, in percentage points.
+    pub humidity: f32,
+    /// The difference in absolute humidity.
+    pub absolute_humidity: f32,
+    /// The difference in CO2, in parts per million.
+    pub co2: i64,
+    /// The difference in estimated CO2, in parts per million, if both samples report it.
+    pub estimated_co2: Option<i64>,
+    /// The difference in estimated CO2 baseline, if both samples report it.
+    pub estimated_co2_baseline: Option<i64>,
+    /// The difference in TVOC, in parts per billion.
+    pub voc: i64,
+    /// The difference in TVOC baseline.
+    pub voc_baseline: i64,
+    /// The difference in the TVOC sensor's H2 reading.
+    pub voc_h2_raw: i64,
+    /// The difference in the TVOC sensor's ethanol reading.
+    pub voc_ethanol_raw: i64,
+    /// The difference in PM2.5, if both samples report it.
+    pub pm25: Option<i64>,
+    /// The difference in estimated PM10, if both samples report it.
+    pub estimated_pm10: Option<i64>,
+    /// The difference in illuminance, in lux, if both samples report it.
+    pub lux: Option<f32>,
+    /// The difference in sound pressure level, in decibels, if both samples report it.
+    pub spl_a: Option<f32>,
+}
+
+/// The signed difference between two model-dependent integer readings.
+fn optional_delta(a: Option<u32>, b: Option<u32>) -> Option<i64> {
+    Some(i64::from(a?) - i64::from(b?))
+}
+
+impl AirData {
+    /// Compute the per-field differences between this sample and an `other`
+    /// (typically older) sample.
+    ///
+    /// Each delta is `self - other`, so a positive delta means that the
+    /// reading is higher in this sample.
+    pub fn diff(&self, other: &AirData) -> AirDataDelta {
+        AirDataDelta {
+            timestamp: self.timestamp - other.timestamp,
+            score: i16::from(self.score) - i16::from(other.score),
+            dew_point: self.dew_point - other.dew_point,
+            temperature: self.temperature - other.temperature,
+            humidity: self.humidity - other.humidity,
+            absolute_humidity: self.absolute_humidity - other.absolute_humidity,
+            co2: i64::from(self.co2) - i64::from(other.co2),
+            estimated_co2: optional_delta(self.estimated_co2, other.estimated_co2),
+            estimated_co2_baseline: optional_delta(
+                self.estimated_co2_baseline,
+                other.estimated_co2_baseline,
+            ),
+            voc: i64::from(self.voc) - i64::from(other.voc),
+            voc_baseline: i64::from(self.voc_baseline) - i64::from(other.voc_baseline),
+            voc_h2_raw: i64::from(self.voc_h2_raw) - i64::from(other.voc_h2_raw),
+            voc_ethanol_raw: i64::from(self.voc_ethanol_raw) - i64::from(other.voc_ethanol_raw),
+            pm25: optional_delta(self.pm25, other.pm25),
+            estimated_pm10: optional_delta(self.estimated_pm10, other.estimated_pm10),
+            lux: self.lux.zip(other.lux).map(|(a, b)| a - b),
+            spl_a: self.spl_a.zip(other.spl_a).map(|(a, b)| a - b),
+        }
+    }
+}
+
+/// The extremes of a single sensor's readings, as tracked by [`SessionStats`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Extremes {
+    /// The lowest reading seen.
+    pub min: f64,
+    /// The highest reading seen.
+    pub max: f64,
+    /// The number of readings seen.
+    pub count: usize,
+}
+
+impl Extremes {
+    fn new(value: f64) -> Self {
+        Self {
+            min: value,
+            max: value,
+            count: 1,
+        }
+    }
+
+    fn update(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.count += 1;
+    }
+}
+
+/// Running statistics over a monitoring session, without retaining each sample.
+///
+/// Sensors are identified by their Local API field names (e.g. `temp` or `co2`).
+#[derive(Clone, Debug, Default)]
+pub struct SessionStats {
+    count: usize,
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+    sensors: Vec<(&'static str, Extremes)>,
+}
+
+impl SessionStats {
+    /// Create a new, empty set of session statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the statistics with a new sample.
+    pub fn update(&mut self, sample: &AirData) {
+        self.count += 1;
+        let timestamp = sample.timestamp;
+        self.first = Some(self.first.map_or(timestamp, |first| first.min(timestamp)));
+        self.last = Some(self.last.map_or(timestamp, |last| last.max(timestamp)));
+
+        for Reading { name, value, .. } in sample.readings() {
+            match self.sensors.iter_mut().find(|(sensor, _)| *sensor == name) {
+                Some((_, extremes)) => extremes.update(value),
+                None => self.sensors.push((name, Extremes::new(value))),
+            }
+        }
+    }
+
+    /// The number of samples seen.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The earliest sample timestamp seen.
+    pub fn first(&self) -> Option<DateTime<Utc>> {
+        self.first
+    }
+
+    /// The latest sample timestamp seen.
+    pub fn last(&self) -> Option<DateTime<Utc>> {
+        self.last
+    }
+
+    /// The time span covered by the samples seen, from the earliest timestamp
+    /// to the latest.
+    pub fn span(&self) -> Option<chrono::Duration> {
+        Some(self.last? - self.first?)
+    }
+
+    /// The extremes of the given sensor's readings, if any have been seen.
+    pub fn sensor(&self, name: &str) -> Option<&Extremes> {
+        self.sensors
+            .iter()
+            .find(|(sensor, _)| *sensor == name)
+            .map(|(_, extremes)| extremes)
+    }
+
+    /// Returns a human-readable, multi-line summary of the session.
+    pub fn summary(&self) -> String {
+        let (Some(first), Some(last)) = (self.first, self.last) else {
+            return "no samples".into();
+        };
+
+        let mut summary = format!(
+            "{} samples from {} to {}",
+            self.count,
+            first.to_rfc3339(),
+            last.to_rfc3339()
+        );
+
+        for (name, extremes) in &self.sensors {
+            // Writing to a String is infallible.
+            let _ = write!(
+                summary,
+                "\n{name}: min {}, max {}",
+                extremes.min, extremes.max
+            );
+        }
+
+        summary
+    }
+}
+
+/// A sensor baseline tracked by a [`BaselineMonitor`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Baseline {
+    /// The VOC sensor's CO2 baseline ([`AirData::estimated_co2_baseline`]).
+    Co2,
+    /// The TVOC sensor's VOC baseline ([`AirData::voc_baseline`]).
+    Voc,
+}
+
+impl Baseline {
+    fn value(self, sample: &AirData) -> Option<u32> {
+        match self {
+            Self::Co2 => sample.estimated_co2_baseline,
+            Self::Voc => Some(sample.voc_baseline),
+        }
+    }
+}
+
+/// Tracks how quickly a sensor baseline drifts, over a fixed-size window of
+/// the most recent samples.
+#[derive(Clone, Debug)]
+pub struct BaselineMonitor {
+    baseline: Baseline,
+    window: usize,
+    threshold: f32,
+    points: VecDeque<(DateTime<Utc>, f32)>,
+}
+
+impl BaselineMonitor {
+    /// Create a new monitor for the given baseline, over the given number of
+    /// samples, that considers drift faster than `threshold` (in baseline
+    /// units per hour, in either direction) to be excessive.
+    ///
+    /// A window of less than two samples is treated as a window of two.
+    pub fn new(baseline: Baseline, window: usize, threshold: f32) -> Self {
+        let window = window.max(2);
+
+        Self {
+            baseline,
+            window,
+            threshold,
+            points: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Add a sample to the window, dropping the oldest sample if the window is full.
+    ///
+    /// Samples that don't report the monitored baseline are ignored.
+    pub fn push(&mut self, sample: &AirData) {
+        let Some(value) = self.baseline.value(sample) else {
+            return;
+        };
+
+        if self.points.len() == self.window {
+            self.points.pop_front();
+        }
+
+        self.points.push_back((sample.timestamp, value as f32));
+    }
+
+    /// The baseline's rate of change over the window, in baseline units per
+    /// hour, as the least-squares slope of the baseline against time.
+    ///
+    /// Returns `None` until the window contains samples with at least two
+    /// distinct timestamps.
+    pub fn drift_rate(&self) -> Option<f32> {
+        let (origin, _) = *self.points.front()?;
+        let points = self
+            .points
+            .iter()
+            .map(|(timestamp, value)| {
+                let hours = (*timestamp - origin).num_milliseconds() as f64 / 3_600_000.0;
+                (hours, f64::from(*value))
+            })
+            .collect::<Vec<_>>();
+
+        let n = points.len() as f64;
+        let mean_hours = points.iter().map(|(hours, _)| hours).sum::<f64>() / n;
+        let mean_value = points.iter().map(|(_, value)| value).sum::<f64>() / n;
+
+        let mut covariance = 0.0;
+        let mut variance = 0.0;
+        for (hours, value) in points {
+            covariance += (hours - mean_hours) * (value - mean_value);
+            variance += (hours - mean_hours).powi(2);
+        }
+
+        (variance > 0.0).then(|| (covariance / variance) as f32)
+    }
+
+    /// Returns whether the baseline is drifting faster than the monitor's threshold.
+    pub fn is_drifting(&self) -> bool {
+        self.drift_rate()
+            .is_some_and(|rate| rate.abs() > self.threshold)
+    }
+}
+
+/// An exponential moving average of a single reading, e.g.
+/// `|sample| f64::from(sample.co2)`.
+#[derive(Clone)]
+pub struct Ema<F> {
+    alpha: f64,
+    selector: F,
+    value: Option<f64>,
+}
+
+impl<F: Fn(&AirData) -> f64> Ema<F> {
+    /// Create a new average with the given smoothing factor, over the reading
+    /// returned by `selector`.
+    ///
+    /// Larger values of `alpha` weigh recent samples more heavily. `alpha` is
+    /// clamped to between 0 and 1.
+    pub fn new(alpha: f64, selector: F) -> Self {
+        Self {
+            alpha: alpha.clamp(0.0, 1.0),
+            selector,
+            value: None,
+        }
+    }
+
+    /// Add a sample to the average, returning the updated average.
+    ///
+    /// The first sample initializes the average to its own reading.
+    pub fn update(&mut self, sample: &AirData) -> f64 {
+        let reading = (self.selector)(sample);
+        let value = match self.value {
+            Some(value) => self.alpha * reading + (1.0 - self.alpha) * value,
+            None => reading,
+        };
+
+        self.value = Some(value);
+        value
+    }
+
+    /// The current average, or `None` if no samples have been added.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+impl<F> fmt::Debug for Ema<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ema")
+            .field("alpha", &self.alpha)
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A reading that changed abnormally between consecutive samples, as
+/// detected by an [`AnomalyDetector`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Anomaly {
+    /// The reading's Local API field name, e.g. `pm25`.
+    pub sensor: &'static str,
+    /// The reading in the previous sample.
+    pub previous: f64,
+    /// The reading in the new sample.
+    pub current: f64,
+    /// How far the change exceeded the reading's threshold, in the reading's units.
+    pub excess: f64,
+}
+
+impl Anomaly {
+    /// The change in the reading, which is positive if it increased.
+    pub fn delta(&self) -> f64 {
+        self.current - self.previous
+    }
+}
+
+/// Detects abnormal jumps in readings between consecutive samples.
+#[derive(Clone, Debug)]
+pub struct AnomalyDetector {
+    thresholds: Vec<(String, f64)>,
+    previous: Vec<Reading>,
+}
+
+impl AnomalyDetector {
+    /// Create a new detector that flags readings whose change between
+    /// consecutive samples (in either direction) exceeds their threshold,
+    /// e.g. `("pm25", 20.0)`.
+    ///
+    /// Readings are identified by their Local API field names, as in
+    /// [`AirData::readings`]. Readings without a threshold are never flagged.
+    pub fn new(thresholds: &[(&str, f64)]) -> Self {
+        Self {
+            thresholds: thresholds
+                .iter()
+                .map(|&(sensor, threshold)| (sensor.into(), threshold))
+                .collect(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// Compare a sample against the previous one, returning the readings that
+    /// changed abnormally in sensor order.
+    ///
+    /// Nothing is flagged for the first sample, or for readings that either
+    /// sample doesn't report.
+    pub fn push(&mut self, sample: &AirData) -> Vec<Anomaly> {
+        let current = sample.readings();
+
+        let anomalies = current
+            .iter()
+            .filter_map(|reading| {
+                let (_, threshold) = self
+                    .thresholds
+                    .iter()
+                    .find(|(sensor, _)| sensor == reading.name)?;
+                let previous = self
+                    .previous
+                    .iter()
+                    .find(|previous| previous.name == reading.name)?;
+
+                let excess = (reading.value - previous.value).abs() - threshold;
+                (excess > 0.0).then_some(Anomaly {
+                    sensor: reading.name,
+                    previous: previous.value,
+                    current: reading.value,
+                    excess,
+                })
+            })
+            .collect();
+
+        self.previous = current;
+        anomalies
+    }
+}
+
+/// The state reported by a [`StaleWatchdog`] for each observed sample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchdogState {
+    /// The sample's timestamp differs from the previous sample's, or this is
+    /// the first sample observed.
+    Fresh,
+    /// The sample's timestamp has repeated this many consecutive times, but
+    /// not enough times to be considered stale.
+    Repeating(u32),
+    /// The sample's timestamp has repeated this many consecutive times, and
+    /// the device has likely frozen.
+    Stale(u32),
+}
+
+impl WatchdogState {
+    /// Returns whether this state is [`WatchdogState::Stale`].
+    pub fn is_stale(self) -> bool {
+        matches!(self, Self::Stale(_))
+    }
+}
+
+/// Detects a frozen device, by counting consecutive samples that repeat the
+/// previous sample's timestamp.
+///
+/// A device only updates its latest sample periodically, so polling faster
+/// than that cadence repeats timestamps too: `max_repeats` should be chosen
+/// with the polling interval in mind (see [`Awair::estimate_update_interval`]).
+///
+/// [`Awair::estimate_update_interval`]: crate::Awair::estimate_update_interval
+#[derive(Clone, Debug)]
+pub struct StaleWatchdog {
+    max_repeats: u32,
+    last: Option<DateTime<Utc>>,
+    repeats: u32,
+}
+
+impl StaleWatchdog {
+    /// Create a new watchdog that considers the device stale once a timestamp
+    /// repeats `max_repeats` consecutive times.
+    ///
+    /// A `max_repeats` of zero is treated as one.
+    pub fn new(max_repeats: u32) -> Self {
+        Self {
+            max_repeats: max_repeats.max(1),
+            last: None,
+            repeats: 0,
+        }
+    }
+
+    /// Observe a sample, returning the watchdog's updated state.
+    ///
+    /// The device stays stale until a sample with a new timestamp is observed.
+    pub fn observe(&mut self, sample: &AirData) -> WatchdogState {
+        if self.last == Some(sample.timestamp) {
+            self.repeats = self.repeats.saturating_add(1);
+        } else {
+            self.last = Some(sample.timestamp);
+            self.repeats = 0;
+        }
+
+        match self.repeats {
+            0 => WatchdogState::Fresh,
+            repeats if repeats >= self.max_repeats => WatchdogState::Stale(repeats),
+            repeats => WatchdogState::Repeating(repeats),
+        }
+    }
+
+    /// Forget all observed samples, e.g. after restarting the device.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.repeats = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    /// Returns the mock's sample, `seconds` later and with the given CO2 reading.
+    fn sample(seconds: i64, co2: u32) -> AirData {
+        let mut sample: AirData = serde_json::from_str(SAMPLE_AIR_DATA).unwrap();
+        sample.timestamp += chrono::Duration::seconds(seconds);
+        sample.co2 = co2;
+        sample
+    }
+
+    #[test]
+    fn test_averager() {
+        let mut averager = AirDataAverager::new(3);
+        assert!(averager.average().is_none());
+
+        averager.push(sample(0, 400));
+        assert_eq!(averager.average().unwrap().co2, 400);
+
+        averager.push(sample(10, 500));
+        let mut third = sample(20, 601);
+        third.temperature = 24.4;
+        third.pm25 = None;
+        averager.push(third);
+
+        let average = averager.average().unwrap();
+        assert_eq!(average.co2, 500);
+        assert!((average.temperature - 22.4).abs() < 1e-4);
+        assert_eq!(average.pm25, Some(3));
+        assert_eq!(average.timestamp, sample(20, 0).timestamp);
+
+        // The oldest sample falls out of the window.
+        averager.push(sample(30, 800));
+        let average = averager.average().unwrap();
+        assert_eq!(average.co2, 634);
+        assert_eq!(average.timestamp, sample(30, 0).timestamp);
+    }
+
+    #[test]
+    fn test_diff() {
+        let older = sample(0, 612);
+        let mut newer = sample(300, 850);
+        newer.score = 80;
+        newer.temperature = 20.9;
+        newer.pm25 = None;
+        newer.lux = Some(12.0);
+
+        let delta = newer.diff(&older);
+        assert_eq!(delta.timestamp, chrono::Duration::minutes(5));
+        assert_eq!(delta.score, -10);
+        assert!((delta.temperature + 0.5).abs() < 1e-4);
+        assert_eq!(delta.co2, 238);
+        assert_eq!(delta.voc, 0);
+        assert_eq!(delta.estimated_co2, Some(0));
+        // Readings that only one sample reports have no delta.
+        assert_eq!(delta.pm25, None);
+        assert_eq!(delta.lux, None);
+
+        let reversed = older.diff(&newer);
+        assert_eq!(reversed.timestamp, chrono::Duration::minutes(-5));
+        assert_eq!(reversed.co2, -238);
+        assert_eq!(reversed.score, 10);
+    }
+
+    #[test]
+    fn test_session_stats() {
+        let mut stats = SessionStats::new();
+        assert_eq!(stats.span(), None);
+        assert_eq!(stats.summary(), "no samples");
+
+        stats.update(&sample(60, 700));
+        let co2 = stats.sensor("co2").unwrap();
+        assert_eq!((co2.min, co2.max, co2.count), (700.0, 700.0, 1));
+        assert_eq!(stats.span(), Some(chrono::Duration::zero()));
+
+        stats.update(&sample(0, 500));
+        stats.update(&sample(600, 900));
+
+        let co2 = stats.sensor("co2").unwrap();
+        assert_eq!((co2.min, co2.max, co2.count), (500.0, 900.0, 3));
+        assert_eq!(stats.count(), 3);
+        assert_eq!(stats.first(), Some(sample(0, 0).timestamp));
+        assert_eq!(stats.span(), Some(chrono::Duration::minutes(10)));
+        assert!(stats.sensor("lux").is_none());
+
+        let summary = stats.summary();
+        assert!(summary
+            .starts_with("3 samples from 2024-01-01T00:00:00+00:00 to 2024-01-01T00:10:00+00:00"));
+        assert!(summary.contains("\nco2: min 500, max 900"));
+    }
+
+    #[test]
+    fn test_baseline_drift() {
+        let mut monitor = BaselineMonitor::new(Baseline::Voc, 4, 500.0);
+        assert_eq!(monitor.drift_rate(), None);
+
+        // A baseline rising by 100 every ten minutes, or 600 per hour.
+        for i in 0..6 {
+            let mut sample = sample(i * 600, 612);
+            sample.voc_baseline = 37_000 + i as u32 * 100;
+            monitor.push(&sample);
+
+            if i == 0 {
+                assert_eq!(monitor.drift_rate(), None);
+            }
+        }
+
+        assert!((monitor.drift_rate().unwrap() - 600.0).abs() < 1e-2);
+        assert!(monitor.is_drifting());
+
+        // A steady baseline, once it fills the window, doesn't drift.
+        let mut steady = BaselineMonitor::new(Baseline::Voc, 4, 500.0);
+        for i in 0..4 {
+            steady.push(&sample(i * 600, 612));
+        }
+        assert_eq!(steady.drift_rate(), Some(0.0));
+        assert!(!steady.is_drifting());
+
+        // Samples without the monitored baseline are ignored.
+        let mut co2 = BaselineMonitor::new(Baseline::Co2, 4, 500.0);
+        let mut unreported = sample(0, 612);
+        unreported.estimated_co2_baseline = None;
+        co2.push(&unreported);
+        co2.push(&sample(600, 612));
+        assert_eq!(co2.drift_rate(), None);
+    }
+
+    #[test]
+    fn test_sort_and_dedup() {
+        let mut samples = vec![
+            sample(20, 1),
+            sample(0, 2),
+            sample(10, 3),
+            sample(0, 4),
+            sample(20, 5),
+        ];
+
+        sort_by_timestamp(&mut samples);
+        let co2 = |samples: &[AirData]| samples.iter().map(|s| s.co2).collect::<Vec<_>>();
+        // The sort is stable.
+        assert_eq!(co2(&samples), [2, 4, 3, 1, 5]);
+
+        dedup_by_timestamp(&mut samples);
+        // The last of each duplicate is kept.
+        assert_eq!(co2(&samples), [4, 3, 5]);
+    }
+
+    #[test]
+    fn test_ema() {
+        let mut ema = Ema::new(0.5, |sample: &AirData| f64::from(sample.co2));
+        assert_eq!(ema.value(), None);
+
+        let expected = [400.0, 500.0, 450.0, 625.0];
+        for (i, (co2, expected)) in [400, 600, 400, 800].into_iter().zip(expected).enumerate() {
+            assert_eq!(ema.update(&sample(i as i64 * 10, co2)), expected);
+        }
+        assert_eq!(ema.value(), Some(625.0));
+
+        // Alpha is clamped: an alpha of 1 (or more) just tracks the latest reading.
+        let mut ema = Ema::new(2.0, |sample: &AirData| f64::from(sample.co2));
+        ema.update(&sample(0, 400));
+        assert_eq!(ema.update(&sample(10, 900)), 900.0);
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let mut detector = AnomalyDetector::new(&[("co2", 200.0), ("pm25", 20.0)]);
+
+        // Nothing to compare the first sample against.
+        assert!(detector.push(&sample(0, 600)).is_empty());
+
+        // Within the threshold.
+        assert!(detector.push(&sample(10, 750)).is_empty());
+
+        let mut spike = sample(20, 1000);
+        spike.pm25 = Some(30);
+        spike.voc = 5000;
+        assert_eq!(
+            detector.push(&spike),
+            [
+                Anomaly {
+                    sensor: "co2",
+                    previous: 750.0,
+                    current: 1000.0,
+                    excess: 50.0,
+                },
+                Anomaly {
+                    sensor: "pm25",
+                    previous: 3.0,
+                    current: 30.0,
+                    excess: 7.0,
+                },
+            ]
+        );
+
+        // Drops count too, and readings that one sample lacks are skipped.
+        let mut drop = sample(30, 500);
+        drop.pm25 = None;
+        let anomalies = detector.push(&drop);
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].delta(), -500.0);
+    }
+
+    #[test]
+    fn test_average_across() {
+        assert!(average_across(&[]).is_none());
+
+        let mut a = sample(0, 400);
+        a.score = 80;
+        a.dew_point = 8.0;
+        a.temperature = 20.0;
+        a.humidity = 40.0;
+        a.absolute_humidity = 7.0;
+        a.estimated_co2 = Some(400);
+        a.voc = 100;
+        a.voc_baseline = 30000;
+        a.pm25 = Some(2);
+        a.estimated_pm10 = None;
+        a.lux = None;
+
+        let mut b = sample(30, 601);
+        b.score = 91;
+        b.dew_point = 10.0;
+        b.temperature = 23.0;
+        b.humidity = 50.0;
+        b.absolute_humidity = 9.0;
+        b.estimated_co2 = Some(500);
+        b.voc = 300;
+        b.voc_baseline = 32000;
+        b.pm25 = Some(5);
+        b.estimated_pm10 = Some(8);
+        b.lux = Some(120.0);
+
+        let average = average_across(&[a, b.clone()]).unwrap();
+        assert_eq!(average.timestamp, b.timestamp);
+        assert_eq!(average.score, 86);
+        assert_eq!(average.dew_point, 9.0);
+        assert_eq!(average.temperature, 21.5);
+        assert_eq!(average.humidity, 45.0);
+        assert_eq!(average.absolute_humidity, 8.0);
+        assert_eq!(average.co2, 501);
+        assert_eq!(average.estimated_co2, Some(450));
+        assert_eq!(average.voc, 200);
+        assert_eq!(average.voc_baseline, 31000);
+        assert_eq!(average.pm25, Some(4));
+        // Model-dependent fields are averaged over the samples that report them.
+        assert_eq!(average.estimated_pm10, Some(8));
+        assert_eq!(average.lux, Some(120.0));
+
+        // A single sample is its own average.
+        assert_eq!(average_across(std::slice::from_ref(&b)).unwrap(), b);
+    }
+
+    #[test]
+    fn test_stale_watchdog() {
+        let mut watchdog = StaleWatchdog::new(3);
+        let frozen = sample(0, 400);
+
+        assert_eq!(watchdog.observe(&frozen), WatchdogState::Fresh);
+        assert_eq!(watchdog.observe(&frozen), WatchdogState::Repeating(1));
+        assert_eq!(watchdog.observe(&frozen), WatchdogState::Repeating(2));
+        assert_eq!(watchdog.observe(&frozen), WatchdogState::Stale(3));
+        assert!(watchdog.observe(&frozen).is_stale());
+
+        // A new timestamp recovers, even if the readings are unchanged.
+        assert_eq!(watchdog.observe(&sample(10, 400)), WatchdogState::Fresh);
+        assert_eq!(
+            watchdog.observe(&sample(10, 400)),
+            WatchdogState::Repeating(1)
+        );
+
+        // After a reset, the next sample is fresh, even with the same timestamp.
+        watchdog.reset();
+        assert_eq!(watchdog.observe(&sample(10, 400)), WatchdogState::Fresh);
+
+        // A zero threshold is treated as one.
+        let mut watchdog = StaleWatchdog::new(0);
+        assert_eq!(watchdog.observe(&frozen), WatchdogState::Fresh);
+        assert_eq!(watchdog.observe(&frozen), WatchdogState::Stale(1));
+    }
+}
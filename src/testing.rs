@@ -0,0 +1,410 @@
+//! A mock Awair, for testing code that uses this crate without a device.
+//!
+//! This is also used by the crate's own tests.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::{
+    AirData, Awair, DeviceConfig, Error, CONFIG_PATH, FIFTEEN_MIN_AVG_PATH, FIVE_MIN_AVG_PATH,
+    LATEST_PATH, RAW_PATH,
+};
+
+/// The air quality sample served by a fresh [`MockAwair`].
+pub(crate) const SAMPLE_AIR_DATA: &str = r#"{
+    "timestamp": "2024-01-01T00:00:00.000Z",
+    "score": 90,
+    "dew_point": 8.5,
+    "temp": 21.4,
+    "humid": 42.1,
+    "abs_humid": 7.9,
+    "co2": 612,
+    "co2_est": 450,
+    "co2_est_baseline": 35000,
+    "voc": 210,
+    "voc_baseline": 37000,
+    "voc_h2_raw": 26,
+    "voc_ethanol_raw": 38,
+    "pm25": 3,
+    "pm10_est": 4
+}"#;
+
+/// The configuration served by a fresh [`MockAwair`].
+pub(crate) const SAMPLE_CONFIG: &str = r#"{
+    "device_uuid": "awair-element_1234",
+    "wifi_mac": "70:88:6B:00:00:01",
+    "ssid": "mock",
+    "ip": "127.0.0.1",
+    "netmask": "255.255.255.0",
+    "gateway": "127.0.0.1",
+    "fw_version": "1.2.4",
+    "timezone": "UTC",
+    "display": "score",
+    "led": { "mode": "auto", "brightness": 100 },
+    "voc_feature_set": 34
+}"#;
+
+/// A request received by a [`MockAwair`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockRequest {
+    /// The request's method, e.g. `GET`.
+    pub method: String,
+    /// The request's path, e.g. `/air-data/latest`.
+    pub path: String,
+    /// The request's headers, with their names lowercased.
+    pub headers: Vec<(String, String)>,
+    /// The request's body.
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// Returns the value of the given (lowercase) header, if present.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A canned HTTP response, for overriding a [`MockAwair`]'s usual responses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Duration,
+}
+
+impl MockResponse {
+    /// Create a response with the given status and body, and no headers.
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Create a successful JSON response with the given body.
+    pub fn json(body: &serde_json::Value) -> Self {
+        Self::new(200, body.to_string()).header("Content-Type", "application/json")
+    }
+
+    /// Add a header to this response.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Wait for the given duration before sending this response, in addition
+    /// to any delay set with [`MockAwair::set_delay`].
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// The canned responses served by a [`MockAwair`], and the requests it's seen.
+#[derive(Debug)]
+struct State {
+    air_data: serde_json::Value,
+    config: serde_json::Value,
+    overrides: Vec<(String, MockResponse)>,
+    queued: VecDeque<MockResponse>,
+    delay: Duration,
+    requests: Vec<MockRequest>,
+}
+
+/// A local HTTP server that mimics an Awair's Local API.
+///
+/// This is intended for tests only: it serves canned responses, and stops
+/// accepting requests when dropped. Settings changes are merged into the
+/// served configuration, so they can be observed by later requests.
+///
+/// Failures can be simulated by overriding responses (with
+/// [`MockAwair::set_response`] and [`MockAwair::queue_response`]) or delaying
+/// them (with [`MockAwair::set_delay`]), and the requests received can be
+/// inspected with [`MockAwair::requests`].
+///
+/// ```
+/// use awair_local_api::{testing::MockAwair, DisplayMode};
+///
+/// let mock = MockAwair::start().unwrap();
+/// let client = mock.awair().unwrap();
+///
+/// assert_eq!(client.poll().unwrap().score, 90);
+/// assert_eq!(client.config().unwrap().display, DisplayMode::Score);
+/// ```
+#[derive(Debug)]
+pub struct MockAwair {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockAwair {
+    /// Start a mock Awair on a random local port, serving sample data.
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let addr = listener.local_addr()?;
+
+        let state = Arc::new(Mutex::new(State {
+            air_data: serde_json::from_str(SAMPLE_AIR_DATA)?,
+            config: serde_json::from_str(SAMPLE_CONFIG)?,
+            overrides: Vec::new(),
+            queued: VecDeque::new(),
+            delay: Duration::ZERO,
+            requests: Vec::new(),
+        }));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let state = Arc::clone(&state);
+            let shutdown = Arc::clone(&shutdown);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    // NOTE: Each exchange gets its own thread, so that a delayed
+                    // response doesn't hold up other requests, and a failed
+                    // exchange only affects its own request.
+                    if let Ok(stream) = stream {
+                        let state = Arc::clone(&state);
+                        thread::spawn(move || serve(stream, &state));
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns the Local API base URL that this mock is serving.
+    pub fn api_base(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Create a client pointed at this mock.
+    pub fn awair(&self) -> Result<Awair, Error> {
+        Awair::new(&self.api_base())
+    }
+
+    /// Serve the given air quality sample from all of the air data endpoints.
+    pub fn set_air_data(&self, air_data: &AirData) {
+        if let Ok(air_data) = serde_json::to_value(air_data) {
+            self.lock().air_data = air_data;
+        }
+    }
+
+    /// Serve the given configuration from the settings endpoint.
+    pub fn set_config(&self, config: &DeviceConfig) {
+        if let Ok(config) = serde_json::to_value(config) {
+            self.lock().config = config;
+        }
+    }
+
+    /// Serve the given response for every request to `path`, instead of the
+    /// usual one.
+    pub fn set_response(&self, path: &str, response: MockResponse) {
+        let mut state = self.lock();
+        state.overrides.retain(|(existing, _)| existing != path);
+        state.overrides.push((path.into(), response));
+    }
+
+    /// Serve the given response for the next request, to any path, instead of
+    /// the usual one. Queued responses are served in order, and take
+    /// precedence over [`MockAwair::set_response`].
+    pub fn queue_response(&self, response: MockResponse) {
+        self.lock().queued.push_back(response);
+    }
+
+    /// Wait for the given duration before responding to each request.
+    pub fn set_delay(&self, delay: Duration) {
+        self.lock().delay = delay;
+    }
+
+    /// Returns the requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for MockAwair {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the server up, so that it notices the shutdown.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Read a single request from the stream, and write its response.
+fn serve(stream: TcpStream, state: &Mutex<State>) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or_default();
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+    state.requests.push(MockRequest {
+        method: method.into(),
+        path: path.into(),
+        headers,
+        body: body.clone(),
+    });
+
+    let response = match state.queued.pop_front() {
+        Some(response) => response,
+        None => respond(&mut state, method, path, &body)?,
+    };
+    let delay = state.delay + response.delay;
+    drop(state);
+
+    thread::sleep(delay);
+
+    let mut stream = &stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\n",
+        response.status,
+        reason(response.status)
+    )?;
+    for (name, value) in &response.headers {
+        write!(stream, "{name}: {value}\r\n")?;
+    }
+    write!(
+        stream,
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        response.body.len()
+    )?;
+    stream.write_all(&response.body)
+}
+
+/// Build the usual response to a request, applying any settings changes.
+fn respond(state: &mut State, method: &str, path: &str, body: &[u8]) -> io::Result<MockResponse> {
+    if let Some((_, response)) = state
+        .overrides
+        .iter()
+        .find(|(existing, _)| existing == path)
+    {
+        return Ok(response.clone());
+    }
+
+    let response = match (method, path) {
+        ("GET", LATEST_PATH | RAW_PATH | FIVE_MIN_AVG_PATH | FIFTEEN_MIN_AVG_PATH) => {
+            MockResponse::json(&state.air_data)
+        }
+        ("GET", CONFIG_PATH) => MockResponse::json(&state.config),
+        ("PUT", CONFIG_PATH) => {
+            let update = serde_json::from_slice::<serde_json::Value>(body)?;
+            if let (Some(config), Some(update)) = (state.config.as_object_mut(), update.as_object())
+            {
+                config.extend(update.clone());
+            }
+            MockResponse::json(&state.config)
+        }
+        _ => MockResponse::new(404, ""),
+    };
+
+    Ok(response)
+}
+
+/// Returns the reason phrase for the given HTTP status.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DisplayMode;
+
+    #[test]
+    fn test_mock_awair_end_to_end() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        assert_eq!(client.poll().unwrap().score, 90);
+        assert_eq!(client.config().unwrap().display, DisplayMode::Score);
+
+        let config = client.set_display_mode(DisplayMode::Co2).unwrap();
+        assert_eq!(config.display, DisplayMode::Co2);
+        assert_eq!(client.config().unwrap().display, DisplayMode::Co2);
+
+        let mut sample = client.poll().unwrap();
+        sample.score = 42;
+        mock.set_air_data(&sample);
+        assert_eq!(client.poll().unwrap().score, 42);
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 6);
+        assert_eq!(requests[2].method, "PUT");
+        assert_eq!(requests[2].path, CONFIG_PATH);
+    }
+
+    #[test]
+    fn test_mock_awair_overrides() {
+        let mock = MockAwair::start().unwrap();
+        let client = mock.awair().unwrap();
+
+        mock.set_response(RAW_PATH, MockResponse::new(404, ""));
+        mock.queue_response(MockResponse::new(500, "oops"));
+
+        assert!(client.poll().is_err());
+        assert!(client.poll().is_ok());
+        assert!(client.poll_raw().is_err());
+        assert!(client.poll_raw().is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Timezone support for [`DeviceConfig`] and [`Awair`], via `chrono-tz`.
+
+use std::sync::{Mutex, PoisonError};
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::{AirData, Awair, DeviceConfig, Error};
+
+impl DeviceConfig {
+    /// Parse the Awair's configured timezone (a TZ database name) into a [`Tz`].
+    pub fn timezone(&self) -> Result<Tz, Error> {
+        self.timezone
+            .parse()
+            .map_err(|_| Error::InvalidTimezone(self.timezone.clone()))
+    }
+}
+
+/// Returns the cached timezone, if any.
+pub(crate) fn cached_timezone(timezone: &Mutex<Option<Tz>>) -> Option<Tz> {
+    *timezone.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Cache the given timezone.
+pub(crate) fn cache_timezone(timezone: &Mutex<Option<Tz>>, tz: Tz) {
+    *timezone.lock().unwrap_or_else(PoisonError::into_inner) = Some(tz);
+}
+
+impl Awair {
+    /// Poll the Awair for its latest air quality data, along with the sample's
+    /// timestamp in the device's configured timezone.
+    ///
+    /// The timezone is read from the device's configuration on first use, and
+    /// cached afterwards. A timezone that isn't a known TZ database name is
+    /// an [`Error::InvalidTimezone`].
+    pub fn poll_local(&self) -> Result<(AirData, DateTime<Tz>), Error> {
+        let tz = match cached_timezone(&self.timezone) {
+            Some(tz) => tz,
+            None => {
+                let tz = self.config()?.timezone()?;
+                cache_timezone(&self.timezone, tz);
+                tz
+            }
+        };
+
+        let data = self.poll()?;
+        let local = data.timestamp_local(&tz);
+        Ok((data, local))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+
+    use super::*;
+    use crate::testing::{MockAwair, MockResponse, SAMPLE_CONFIG};
+
+    fn config(timezone: &str) -> DeviceConfig {
+        let mut config: DeviceConfig = serde_json::from_str(SAMPLE_CONFIG).unwrap();
+        config.timezone = timezone.into();
+        config
+    }
+
+    #[test]
+    fn test_timezone() {
+        assert_eq!(
+            config("America/New_York").timezone().unwrap(),
+            chrono_tz::America::New_York
+        );
+        assert!(matches!(
+            config("Mars/Olympus_Mons").timezone(),
+            Err(Error::InvalidTimezone(tz)) if tz == "Mars/Olympus_Mons"
+        ));
+    }
+
+    #[test]
+    fn test_poll_local() {
+        let mock = MockAwair::start().unwrap();
+        mock.set_response(
+            "/settings/config/data",
+            MockResponse::json(&serde_json::to_value(config("Asia/Tokyo")).unwrap()),
+        );
+        let client = Awair::new(&mock.api_base()).unwrap();
+
+        let (data, local) = client.poll_local().unwrap();
+        assert_eq!(local, data.timestamp);
+        assert_eq!(local.timezone(), chrono_tz::Asia::Tokyo);
+        assert_eq!(local.hour(), 9);
+
+        // The timezone is cached after the first poll.
+        client.poll_local().unwrap();
+        assert_eq!(
+            mock.requests()
+                .iter()
+                .filter(|request| request.path == "/settings/config/data")
+                .count(),
+            1
+        );
+
+        let bad = MockAwair::start().unwrap();
+        bad.set_response(
+            "/settings/config/data",
+            MockResponse::json(&serde_json::to_value(config("Nowhere/Special")).unwrap()),
+        );
+        let client = Awair::new(&bad.api_base()).unwrap();
+        assert!(matches!(
+            client.poll_local(),
+            Err(Error::InvalidTimezone(tz)) if tz == "Nowhere/Special"
+        ));
+    }
+}
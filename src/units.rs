@@ -0,0 +1,225 @@
+//! Unit-carrying newtypes for air quality readings.
+//!
+//! These wrap the plain numbers in [`AirData`] so that readings with
+//! different units can't be accidentally mixed up. Each newtype serializes
+//! transparently as its underlying number, so [`TypedAirData`] shares its
+//! wire format with [`AirData`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{AirData, ScoreBreakdown};
+
+macro_rules! unit {
+    ($(#[$meta:meta])* $name:ident($inner:ty), $suffix:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        impl $name {
+            /// Returns the underlying number.
+            pub fn into_inner(self) -> $inner {
+                self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl From<$inner> for $name {
+            fn from(value: $inner) -> Self {
+                Self(value)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                // Forward to the underlying number's formatting, so that
+                // precision and other flags are respected.
+                fmt::Display::fmt(&self.0, f)?;
+                f.write_str($suffix)
+            }
+        }
+    };
+}
+
+unit!(
+    /// A temperature, in degrees Celsius.
+    Celsius(f32),
+    "°C"
+);
+
+unit!(
+    /// A concentration, in parts per million.
+    Ppm(u32),
+    "ppm"
+);
+
+unit!(
+    /// A concentration, in parts per billion.
+    Ppb(u32),
+    "ppb"
+);
+
+unit!(
+    /// An illuminance, in lux.
+    Lux(f32),
+    "lx"
+);
+
+unit!(
+    /// An A-weighted sound pressure level, in decibels.
+    Dba(f32),
+    "dBA"
+);
+
+unit!(
+    /// A percentage.
+    Percent(f32),
+    "%"
+);
+
+/// A sample of air quality data, with each reading wrapped in a newtype
+/// carrying its unit.
+///
+/// This mirrors [`AirData`] field-for-field, and (de)serializes identically.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "AirData")]
+pub struct TypedAirData {
+    /// The time reported by the device's internal clock.
+    pub timestamp: DateTime<Utc>,
+    /// The Awair Score, from 0-100.
+    pub score: u8,
+    /// The dew point.
+    pub dew_point: Celsius,
+    /// The dry bulb temperature.
+    #[serde(rename = "temp")]
+    pub temperature: Celsius,
+    /// The relative humidity.
+    #[serde(rename = "humid")]
+    pub humidity: Percent,
+    /// The absolute humidity.
+    #[serde(rename = "abs_humid")]
+    pub absolute_humidity: f32,
+    /// The CO2 reading.
+    pub co2: Ppm,
+    /// The VOC sensor's estimated CO2 reading, if reported.
+    #[serde(rename = "co2_est", default)]
+    pub estimated_co2: Option<Ppm>,
+    /// The VOC sensor's CO2 baseline (unitless), if reported.
+    #[serde(rename = "co2_est_baseline", default)]
+    pub estimated_co2_baseline: Option<u32>,
+    /// The TVOC reading.
+    pub voc: Ppb,
+    /// The TVOC sensor's VOC baseline (unitless).
+    pub voc_baseline: u32,
+    /// The TVOC sensor's H2 (hydrogen gas) reading (unitless).
+    pub voc_h2_raw: u32,
+    /// The TVOC sensor's ethanol gas reading (unitless).
+    pub voc_ethanol_raw: u32,
+    /// The PM2.5 reading (in micrograms per cubic meter), if reported.
+    #[serde(default)]
+    pub pm25: Option<u32>,
+    /// The PM10 reading (in micrograms per cubic meter), if reported.
+    #[serde(rename = "pm10_est", default)]
+    pub estimated_pm10: Option<u32>,
+    /// The illuminance, if reported.
+    #[serde(default)]
+    pub lux: Option<Lux>,
+    /// The A-weighted sound pressure level, if reported.
+    #[serde(default)]
+    pub spl_a: Option<Dba>,
+    /// The per-factor contributions to the Awair Score, if reported.
+    #[serde(default)]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    /// Any fields reported by the device that aren't otherwise modeled.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl From<&AirData> for TypedAirData {
+    fn from(data: &AirData) -> Self {
+        Self {
+            timestamp: data.timestamp,
+            score: data.score,
+            dew_point: data.dew_point.into(),
+            temperature: data.temperature.into(),
+            humidity: data.humidity.into(),
+            absolute_humidity: data.absolute_humidity,
+            co2: data.co2.into(),
+            estimated_co2: data.estimated_co2.map(Into::into),
+            estimated_co2_baseline: data.estimated_co2_baseline,
+            voc: data.voc.into(),
+            voc_baseline: data.voc_baseline,
+            voc_h2_raw: data.voc_h2_raw,
+            voc_ethanol_raw: data.voc_ethanol_raw,
+            pm25: data.pm25,
+            estimated_pm10: data.estimated_pm10,
+            lux: data.lux.map(Into::into),
+            spl_a: data.spl_a.map(Into::into),
+            score_breakdown: data.score_breakdown,
+            extra: data.extra.clone(),
+        }
+    }
+}
+
+impl From<AirData> for TypedAirData {
+    fn from(data: AirData) -> Self {
+        (&data).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_air_data_round_trip() {
+        let payload = serde_json::json!({
+            "timestamp": "2024-01-01T00:00:00Z",
+            "score": 90,
+            "dew_point": 8.5,
+            "temp": 21.4,
+            "humid": 42.1,
+            "abs_humid": 7.9,
+            "co2": 612,
+            "co2_est": 450,
+            "co2_est_baseline": 35000,
+            "voc": 210,
+            "voc_baseline": 37000,
+            "voc_h2_raw": 26,
+            "voc_ethanol_raw": 38,
+            "pm25": 3,
+            "pm10_est": 4,
+            "lux": 120.5,
+            "spl_a": 45.0,
+            "score_breakdown": { "temp": 1.0, "humid": 2.0, "co2": 3.0, "voc": 4.0, "pm25": 5.0 },
+            "made_up": "extra",
+        });
+
+        let typed: TypedAirData = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(*typed.temperature, 21.4);
+        assert_eq!(typed.lux.map(|lux| *lux), Some(120.5));
+        assert_eq!(typed.extra["made_up"], "extra");
+
+        let plain: AirData = serde_json::from_value(payload).unwrap();
+        assert_eq!(
+            serde_json::to_value(&typed).unwrap(),
+            serde_json::to_value(&plain).unwrap()
+        );
+
+        let again: TypedAirData =
+            serde_json::from_value(serde_json::to_value(&typed).unwrap()).unwrap();
+        assert_eq!(again, typed);
+    }
+}
@@ -0,0 +1,90 @@
+//! Simple ventilation advice from indoor air quality readings.
+
+use std::fmt;
+
+use crate::comfort::COMFORTABLE_HUMIDITY;
+use crate::{AirData, Co2Level, Pm25Level, VocLevel};
+
+/// An actionable ventilation recommendation, as returned by
+/// [`AirData::ventilation_advice`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ventilation {
+    /// No action is needed.
+    Fine,
+    /// Bring in fresh air, e.g. by opening a window.
+    OpenWindow,
+    /// Run a fan, e.g. an exhaust fan or an air purifier.
+    TurnOnFan,
+}
+
+impl fmt::Display for Ventilation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Fine => "fine",
+            Self::OpenWindow => "open a window",
+            Self::TurnOnFan => "turn on a fan",
+        })
+    }
+}
+
+impl AirData {
+    /// Recommend a ventilation action for this sample.
+    ///
+    /// The recommendation is based only on indoor readings, in order of
+    /// priority:
+    ///
+    /// 1. If CO2 or TVOC is [poor](Co2Level::Poor) or worse, fresh air is
+    ///    needed to dilute them: [`Ventilation::OpenWindow`].
+    /// 2. Otherwise, if PM2.5 is [poor](Pm25Level::Poor) or worse, the
+    ///    particulates may well have come from outdoors, so opening a window
+    ///    isn't advised: [`Ventilation::TurnOnFan`] instead, for filtration.
+    /// 3. Otherwise, if the relative humidity is above 60%,
+    ///    [`Ventilation::TurnOnFan`], for exhaust.
+    /// 4. Otherwise, [`Ventilation::Fine`].
+    pub fn ventilation_advice(&self) -> Ventilation {
+        let (_, max_humidity) = COMFORTABLE_HUMIDITY;
+
+        if self.co2_level() >= Co2Level::Poor || self.voc_level() >= VocLevel::Poor {
+            Ventilation::OpenWindow
+        } else if self.pm25_level() >= Some(Pm25Level::Poor) || self.humidity > max_humidity {
+            Ventilation::TurnOnFan
+        } else {
+            Ventilation::Fine
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SAMPLE_AIR_DATA;
+
+    fn sample() -> AirData {
+        serde_json::from_str(SAMPLE_AIR_DATA).unwrap()
+    }
+
+    #[test]
+    fn test_ventilation_advice() {
+        assert_eq!(sample().ventilation_advice(), Ventilation::Fine);
+
+        let mut stuffy = sample();
+        stuffy.co2 = 1000;
+        assert_eq!(stuffy.ventilation_advice(), Ventilation::OpenWindow);
+
+        let mut fumes = sample();
+        fumes.voc = 1000;
+        assert_eq!(fumes.ventilation_advice(), Ventilation::OpenWindow);
+
+        let mut smoky = sample();
+        smoky.pm25 = Some(35);
+        assert_eq!(smoky.ventilation_advice(), Ventilation::TurnOnFan);
+
+        let mut humid = sample();
+        humid.humidity = 61.0;
+        assert_eq!(humid.ventilation_advice(), Ventilation::TurnOnFan);
+
+        // Stale air takes precedence over particulates.
+        smoky.co2 = 1500;
+        assert_eq!(smoky.ventilation_advice(), Ventilation::OpenWindow);
+    }
+}